@@ -1,4 +1,10 @@
 use clap::{arg, Parser};
+use ore_api::consts::TOLERANCE;
+
+use crate::{
+    backend::Backend, bus_strategy::BusStrategy, mine::MineStrategy,
+    tip_stream::DEFAULT_TIP_STREAM_URL, utils::TipPercentile, DEFAULT_JITO_TIP,
+};
 
 #[derive(Parser, Debug)]
 pub struct BalanceArgs {
@@ -20,6 +26,33 @@ pub struct BenchmarkArgs {
         default_value = "1"
     )]
     pub threads: u64,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "How long to run the benchmark for",
+        default_value = "30",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub duration: u64,
+
+    #[arg(
+        long,
+        help = "Pin each benchmark thread to its own core, as the miner does"
+    )]
+    pub pin: bool,
+
+    #[arg(long, help = "Print the results as JSON")]
+    pub json: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "BACKEND",
+        help = "The hashing backend to benchmark",
+        default_value = "cpu"
+    )]
+    pub backend: Backend,
 }
 
 #[derive(Parser, Debug)]
@@ -66,6 +99,139 @@ pub struct MineArgs {
         default_value = "20"
     )]
     pub diff: u32,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "STRATEGY",
+        help = "When to stop hashing relative to --diff and the submission deadline",
+        default_value = "max"
+    )]
+    pub strategy: MineStrategy,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "BACKEND",
+        help = "The hashing backend to mine with",
+        default_value = "cpu"
+    )]
+    pub backend: Backend,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "STRATEGY",
+        help = "How to choose the bus each solution is paid out from",
+        default_value = "largest"
+    )]
+    pub bus_strategy: BusStrategy,
+
+    #[arg(
+        long,
+        help = "On Ctrl-C or SIGTERM, submit the best solution found so far if it meets the minimum difficulty"
+    )]
+    pub submit_on_exit: bool,
+
+    #[arg(
+        long,
+        short,
+        value_name = "SECONDS",
+        help = "The number of seconds before the one-minute mark to stop hashing and submit. At most 5, since the program rejects earlier submissions.",
+        default_value = "5",
+        value_parser = clap::value_parser!(u64).range(..=TOLERANCE as u64)
+    )]
+    pub buffer_time: u64,
+
+    #[arg(
+        long,
+        short,
+        value_name = "THREAD_COUNT",
+        help = "The number of threads to mine with. Defaults to one per core.",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub threads: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "CORES",
+        help = "Cores to pin mining threads to, as a list of ids and ranges (e.g. 0-7,16-23)"
+    )]
+    pub cores: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "INDEX",
+        help = "Index of this machine among --nonce-stride machines mining with the same keypair",
+        default_value = "0"
+    )]
+    pub nonce_offset: u64,
+
+    #[arg(
+        long,
+        value_name = "MACHINE_COUNT",
+        help = "Number of machines mining with the same keypair. Each searches a disjoint nonce range.",
+        default_value = "1",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub nonce_stride: u64,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "PERCENTILE",
+        help = "Percentile of recently landed Jito tips to pay",
+        default_value = "50"
+    )]
+    pub tip_percentile: TipPercentile,
+
+    #[arg(
+        long,
+        value_name = "MULTIPLIER",
+        help = "Multiplier applied to the selected Jito tip percentile",
+        default_value = "1.0"
+    )]
+    pub tip_multiplier: f64,
+
+    #[arg(
+        long,
+        value_name = "LAMPORTS",
+        help = "Minimum Jito tip to pay per mine transaction",
+        default_value = "0"
+    )]
+    pub tip_min: u64,
+
+    #[arg(
+        long,
+        value_name = "LAMPORTS",
+        help = "Maximum Jito tip to pay per mine transaction",
+        default_value = "100000"
+    )]
+    pub tip_max: u64,
+
+    #[arg(
+        long,
+        value_name = "URL",
+        help = "Websocket that publishes recently landed Jito tips",
+        default_value = DEFAULT_TIP_STREAM_URL
+    )]
+    pub tip_stream_url: String,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Seconds without a tip stream update before the last tip is considered stale",
+        default_value = "30"
+    )]
+    pub tip_stale_after: u64,
+
+    #[arg(
+        long,
+        value_name = "LAMPORTS",
+        help = "Jito tip to pay while the tip stream is unavailable or stale",
+        default_value_t = DEFAULT_JITO_TIP
+    )]
+    pub tip_fallback: u64,
 }
 
 #[derive(Parser, Debug)]
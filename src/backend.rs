@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use clap::ValueEnum;
+use drillx::{equix, Hash};
+
+/// A source of drillx hashes, shared by every hashing thread.
+pub trait HashBackend: Send + Sync {
+    /// Creates the hashing state owned by a single thread. This is called on the
+    /// thread that will use it, after it has been pinned to its core.
+    fn worker(&self) -> Box<dyn HashWorker>;
+}
+
+/// The per-thread half of a `HashBackend`.
+pub trait HashWorker {
+    /// Returns the hash for the nonce, or `None` if the nonce has no solution.
+    fn hash(&mut self, challenge: &[u8; 32], nonce: u64) -> Option<Hash>;
+}
+
+/// The hashing backends that can be selected with --backend.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Backend {
+    /// Hash on the CPU, reusing one solver allocation per thread.
+    Cpu,
+}
+
+impl Backend {
+    pub fn build(self) -> Arc<dyn HashBackend> {
+        match self {
+            Backend::Cpu => Arc::new(CpuBackend),
+        }
+    }
+}
+
+pub struct CpuBackend;
+
+impl HashBackend for CpuBackend {
+    fn worker(&self) -> Box<dyn HashWorker> {
+        Box::new(CpuWorker {
+            memory: equix::SolverMemory::new(),
+        })
+    }
+}
+
+struct CpuWorker {
+    memory: equix::SolverMemory,
+}
+
+impl HashWorker for CpuWorker {
+    fn hash(&mut self, challenge: &[u8; 32], nonce: u64) -> Option<Hash> {
+        drillx::hash_with_memory(&mut self.memory, challenge, &nonce.to_le_bytes()).ok()
+    }
+}
+
+/// A backend for tests that returns hashes of scripted difficulties: nonce `n` hashes
+/// to difficulty `difficulties[n]`, and to zero past the end of the script.
+#[cfg(test)]
+pub struct ScriptedBackend {
+    pub difficulties: Vec<u32>,
+}
+
+#[cfg(test)]
+impl HashBackend for ScriptedBackend {
+    fn worker(&self) -> Box<dyn HashWorker> {
+        Box::new(ScriptedWorker {
+            difficulties: self.difficulties.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+struct ScriptedWorker {
+    difficulties: Vec<u32>,
+}
+
+#[cfg(test)]
+impl HashWorker for ScriptedWorker {
+    fn hash(&mut self, _challenge: &[u8; 32], nonce: u64) -> Option<Hash> {
+        let difficulty = self.difficulties.get(nonce as usize).copied().unwrap_or(0);
+        let mut h = [0xff; 32];
+        for bit in 0..difficulty as usize {
+            h[bit / 8] &= !(0x80 >> (bit % 8));
+        }
+        Some(Hash { d: [0; 16], h })
+    }
+}
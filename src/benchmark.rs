@@ -1,10 +1,20 @@
-use std::{sync::Arc, time::Instant};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
+use clap::ValueEnum;
+use colored::*;
+use serde_json::json;
 use solana_rpc_client::spinner;
 
 use crate::{args::BenchmarkArgs, Miner};
 
-const TEST_DURATION: i64 = 30;
+/// Difficulties at or above this are counted in the last histogram bucket.
+const MAX_DIFFICULTY: usize = 64;
+
+/// How many difficulties past the best one found to estimate times for.
+const EXTRA_DIFFICULTIES: usize = 4;
 
 impl Miner {
     pub async fn benchmark(&self, args: BenchmarkArgs) {
@@ -13,50 +23,141 @@ impl Miner {
 
         // Dispatch job to each thread
         let challenge = [0; 32];
+        let duration = Duration::from_secs(args.duration);
+        let cores = core_affinity::get_core_ids().unwrap_or_default();
+        if args.pin && cores.is_empty() {
+            eprintln!(
+                "{} Could not list the available cores, running unpinned",
+                "WARNING".bold().yellow()
+            );
+        }
         let progress_bar = Arc::new(spinner::new_progress_bar());
         progress_bar.set_message(format!(
             "Benchmarking. This will take {} sec...",
-            TEST_DURATION
+            args.duration
         ));
+        let backend = args.backend.build();
         let handles: Vec<_> = (0..args.threads)
             .map(|i| {
+                let core_id =
+                    (args.pin && !cores.is_empty()).then(|| cores[i as usize % cores.len()]);
                 std::thread::spawn({
+                    let backend = Arc::clone(&backend);
                     move || {
+                        if let Some(core_id) = core_id {
+                            if !core_affinity::set_for_current(core_id) {
+                                eprintln!(
+                                    "{} Could not pin a benchmark thread to core {}",
+                                    "WARNING".bold().yellow(),
+                                    core_id.id
+                                );
+                            }
+                        }
+                        let mut hasher = backend.worker();
+                        let mut histogram = [0u64; MAX_DIFFICULTY + 1];
                         let timer = Instant::now();
                         let first_nonce = u64::MAX.saturating_div(args.threads).saturating_mul(i);
                         let mut nonce = first_nonce;
                         loop {
                             // Create hash
-                            let _hx = drillx::hash(&challenge, &nonce.to_le_bytes());
+                            if let Some(hx) = hasher.hash(&challenge, nonce) {
+                                histogram[(hx.difficulty() as usize).min(MAX_DIFFICULTY)] += 1;
+                            }
 
                             // Increment nonce
                             nonce += 1;
 
                             // Exit if time has elapsed
-                            if (timer.elapsed().as_secs() as i64).ge(&TEST_DURATION) {
+                            if timer.elapsed() >= duration {
                                 break;
                             }
                         }
 
-                        // Return hash count
-                        nonce - first_nonce
+                        // Return hash count and difficulties found
+                        (nonce - first_nonce, histogram)
                     }
                 })
             })
             .collect();
 
-        // Join handles and return best nonce
+        // Join handles and merge the results
         let mut total_nonces = 0;
+        let mut histogram = [0u64; MAX_DIFFICULTY + 1];
         for h in handles {
-            if let Ok(count) = h.join() {
+            if let Ok((count, thread_histogram)) = h.join() {
                 total_nonces += count;
+                for (total, count) in histogram.iter_mut().zip(thread_histogram) {
+                    *total += count;
+                }
             }
         }
+        let hashrate = total_nonces as f64 / duration.as_secs_f64();
+
+        // Estimate times up to a little past the best difficulty found. Each hash
+        // reaches difficulty d with probability 2^-d.
+        let best = histogram.iter().rposition(|&count| count > 0).unwrap_or(0);
+        let rows: Vec<_> = (0..=(best + EXTRA_DIFFICULTIES).min(MAX_DIFFICULTY))
+            .map(|difficulty| {
+                let expected_secs = 2f64.powi(difficulty as i32) / hashrate;
+                (difficulty, histogram[difficulty], expected_secs)
+            })
+            .collect();
 
         // Update log
         progress_bar.finish_with_message(format!(
             "Hashpower: {} H/sec",
-            total_nonces.saturating_div(TEST_DURATION as u64),
+            total_nonces.saturating_div(args.duration.max(1)),
         ));
+
+        if args.json {
+            let report = json!({
+                "backend": args.backend.to_possible_value().unwrap().get_name(),
+                "threads": args.threads,
+                "pinned": args.pin,
+                "duration_secs": args.duration,
+                "hashes": total_nonces,
+                "hashrate": hashrate,
+                "difficulties": rows
+                    .iter()
+                    .map(|(difficulty, count, expected_secs)| json!({
+                        "difficulty": difficulty,
+                        "count": count,
+                        "expected_secs": expected_secs,
+                    }))
+                    .collect::<Vec<_>>(),
+            });
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+            return;
+        }
+
+        println!(
+            "{:>10}  {:>12}  {:>16}",
+            "Difficulty".bold(),
+            "Found".bold(),
+            "Expected time".bold()
+        );
+        for (difficulty, count, expected_secs) in rows {
+            println!(
+                "{:>10}  {:>12}  {:>16}",
+                difficulty,
+                count,
+                format_secs(expected_secs)
+            );
+        }
+    }
+}
+
+/// Formats a duration in seconds with a unit that keeps it readable.
+fn format_secs(secs: f64) -> String {
+    if !secs.is_finite() {
+        "n/a".to_string()
+    } else if secs < 1.0 {
+        format!("{:.1} ms", secs * 1000.0)
+    } else if secs < 120.0 {
+        format!("{:.1} sec", secs)
+    } else if secs < 7200.0 {
+        format!("{:.1} min", secs / 60.0)
+    } else {
+        format!("{:.1} h", secs / 3600.0)
     }
 }
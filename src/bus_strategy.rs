@@ -0,0 +1,173 @@
+use std::time::Instant;
+
+use clap::ValueEnum;
+use ore_api::{
+    consts::{BUS_ADDRESSES, BUS_COUNT},
+    state::{Bus, Config},
+};
+use ore_utils::AccountDeserialize;
+use rand::{distributions::WeightedIndex, prelude::*};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig},
+};
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_sdk::{signature::Signer, transaction::Transaction};
+
+use crate::Miner;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum BusStrategy {
+    /// Always pick the bus with the largest balance.
+    Largest,
+    /// Pick a random bus, weighted by balance.
+    Weighted,
+    /// Pick the bus this miner used least recently, among those with rewards left.
+    Lru,
+    /// Pick a random bus among those that can cover the expected reward, falling back
+    /// to the largest.
+    Covering,
+}
+
+/// Returns the reward, in grains, the program would pay for a solution of the given
+/// difficulty before it is capped by the bus balance.
+pub fn expected_reward(config: &Config, difficulty: u32, multiplier: f64) -> u64 {
+    let extra = difficulty.saturating_sub(config.min_difficulty as u32);
+    let reward = config
+        .base_reward_rate
+        .saturating_mul(2u64.saturating_pow(extra));
+    (reward as f64 * multiplier) as u64
+}
+
+/// Chooses the bus each mine transaction pays out from, remembering the last bus
+/// balances seen and when each bus was last used.
+pub struct BusSelector {
+    strategy: BusStrategy,
+    snapshot: Option<Vec<Bus>>,
+    last_used: [Option<Instant>; BUS_COUNT],
+}
+
+impl BusSelector {
+    pub fn new(strategy: BusStrategy) -> Self {
+        Self {
+            strategy,
+            snapshot: None,
+            last_used: [None; BUS_COUNT],
+        }
+    }
+
+    /// Picks a bus for a solution expected to earn `expected_reward`, avoiding the
+    /// `excluded` busses unless no other bus is left. Falls back to the last bus
+    /// snapshot if the busses cannot be fetched, and to a random bus if there is no
+    /// snapshot yet.
+    pub async fn select(
+        &mut self,
+        client: &RpcClient,
+        expected_reward: u64,
+        excluded: &[Pubkey],
+    ) -> Pubkey {
+        if let Some(busses) = fetch_busses(client).await {
+            self.snapshot = Some(busses);
+        }
+        let is_allowed = |id: usize| !excluded.contains(&BUS_ADDRESSES[id]);
+        let id = match &self.snapshot {
+            Some(busses) => {
+                let allowed: Vec<Bus> = busses
+                    .iter()
+                    .filter(|bus| is_allowed(bus.id as usize))
+                    .copied()
+                    .collect();
+                if allowed.is_empty() {
+                    self.pick(busses, expected_reward)
+                } else {
+                    self.pick(&allowed, expected_reward)
+                }
+            }
+            None => {
+                let allowed: Vec<usize> = (0..BUS_COUNT).filter(|&id| is_allowed(id)).collect();
+                let mut rng = rand::thread_rng();
+                allowed
+                    .choose(&mut rng)
+                    .copied()
+                    .unwrap_or_else(|| rng.gen_range(0..BUS_COUNT))
+            }
+        };
+        self.last_used[id] = Some(Instant::now());
+        BUS_ADDRESSES[id]
+    }
+
+    fn pick(&self, busses: &[Bus], expected_reward: u64) -> usize {
+        let largest = busses
+            .iter()
+            .max_by_key(|bus| bus.rewards)
+            .map_or(0, |bus| bus.id as usize);
+        let mut rng = rand::thread_rng();
+        match self.strategy {
+            BusStrategy::Largest => largest,
+            BusStrategy::Weighted => WeightedIndex::new(busses.iter().map(|bus| bus.rewards))
+                .map_or(largest, |weights| {
+                    busses[weights.sample(&mut rng)].id as usize
+                }),
+            BusStrategy::Lru => busses
+                .iter()
+                .filter(|bus| bus.rewards > 0)
+                .min_by_key(|bus| self.last_used[bus.id as usize])
+                .map_or(largest, |bus| bus.id as usize),
+            BusStrategy::Covering => {
+                let covering: Vec<&Bus> = busses
+                    .iter()
+                    .filter(|bus| bus.rewards >= expected_reward)
+                    .collect();
+                covering
+                    .choose(&mut rng)
+                    .map_or(largest, |bus| bus.id as usize)
+            }
+        }
+    }
+}
+
+impl Miner {
+    /// Simulates the mine transaction and returns true if it would empty the bus it
+    /// pays out from, meaning the reward was cut to whatever the bus had left.
+    pub async fn is_bus_exhausted(&self, ixs: &[Instruction], bus: Pubkey) -> bool {
+        let tx = Transaction::new_with_payer(ixs, Some(&self.signer().pubkey()));
+        let sim_cfg = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(self.write_client.commitment()),
+            accounts: Some(RpcSimulateTransactionAccountsConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                addresses: vec![bus.to_string()],
+            }),
+            ..RpcSimulateTransactionConfig::default()
+        };
+        let Ok(sim) = self
+            .write_client
+            .simulate_transaction_with_config(&tx, sim_cfg)
+            .await
+        else {
+            return false;
+        };
+
+        // Read the bus balance left after the simulated payout
+        let remaining = sim
+            .value
+            .accounts
+            .and_then(|accounts| accounts.into_iter().next().flatten())
+            .and_then(|account| account.data.decode())
+            .and_then(|data| Bus::try_from_bytes(&data).ok().map(|bus| bus.rewards));
+        remaining == Some(0)
+    }
+}
+
+async fn fetch_busses(client: &RpcClient) -> Option<Vec<Bus>> {
+    let accounts = client.get_multiple_accounts(&BUS_ADDRESSES).await.ok()?;
+    let busses: Vec<Bus> = accounts
+        .into_iter()
+        .flatten()
+        .filter_map(|account| Bus::try_from_bytes(&account.data).ok().copied())
+        .filter(|bus| (bus.id as usize) < BUS_COUNT)
+        .collect();
+    (!busses.is_empty()).then_some(busses)
+}
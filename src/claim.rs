@@ -9,14 +9,14 @@ use spl_token::amount_to_ui_amount;
 use crate::{
     args::ClaimArgs,
     cu_limits::CU_LIMIT_CLAIM,
-    send_and_confirm::ComputeBudget,
+    send_and_confirm::{ ComputeBudget, TxOutcome },
     utils::{ amount_f64_to_u64, ask_confirm, get_proof_with_authority },
     Miner,
     DEFAULT_JITO_TIP,
 };
 
 impl Miner {
-    pub async fn claim(&self, args: ClaimArgs) {
+    pub async fn claim(&self, args: ClaimArgs) -> bool {
         let signer = self.signer();
         let pubkey = signer.pubkey();
         let proof = get_proof_with_authority(&self.rpc_client, pubkey).await;
@@ -41,7 +41,13 @@ impl Miner {
                 }
                 benefiary_tokens
             }
-            None => self.initialize_ata().await,
+            None =>
+                match self.initialize_ata().await {
+                    Some(token_account) => token_account,
+                    None => {
+                        return false;
+                    }
+                }
         };
 
         // Parse amount to claim
@@ -63,19 +69,31 @@ impl Miner {
                 ).as_str()
             )
         {
-            return;
+            return false;
         }
 
         // Send and confirm
         ixs.push(ore_api::instruction::claim(pubkey, beneficiary, amount));
-        self.send_and_confirm(
-            &ixs,
-            ComputeBudget::Fixed(CU_LIMIT_CLAIM),
-            DEFAULT_JITO_TIP
-        ).await.ok();
+        match
+            self.send_and_confirm(
+                &ixs,
+                ComputeBudget::Fixed(CU_LIMIT_CLAIM),
+                DEFAULT_JITO_TIP
+            ).await
+        {
+            Ok(TxOutcome::Landed(_)) => true,
+            Ok(outcome) => {
+                println!("{} Claim did not land: {}", "ERROR".bold().red(), outcome);
+                false
+            }
+            Err(err) => {
+                println!("{} {}", "ERROR".bold().red(), err);
+                false
+            }
+        }
     }
 
-    async fn initialize_ata(&self) -> Pubkey {
+    async fn initialize_ata(&self) -> Option<Pubkey> {
         // Initialize client.
         let signer = self.signer();
         let client = self.rpc_client.clone();
@@ -88,7 +106,7 @@ impl Miner {
 
         // Check if ata already exists
         if let Ok(Some(_ata)) = client.get_token_account(&token_account_pubkey).await {
-            return token_account_pubkey;
+            return Some(token_account_pubkey);
         }
         // Sign and send transaction.
         let ix = spl_associated_token_account::instruction::create_associated_token_account(
@@ -97,9 +115,19 @@ impl Miner {
             &ore_api::consts::MINT_ADDRESS,
             &spl_token::id()
         );
-        self.send_and_confirm(&[ix], ComputeBudget::Dynamic, DEFAULT_JITO_TIP).await.ok();
+        match self.send_and_confirm(&[ix], ComputeBudget::Dynamic, DEFAULT_JITO_TIP).await {
+            Ok(TxOutcome::Landed(_)) => {}
+            Ok(outcome) => {
+                println!("{} Failed to create token account: {}", "ERROR".bold().red(), outcome);
+                return None;
+            }
+            Err(err) => {
+                println!("{} {}", "ERROR".bold().red(), err);
+                return None;
+            }
+        }
 
         // Return token account address
-        token_account_pubkey
+        Some(token_account_pubkey)
     }
 }
@@ -4,7 +4,7 @@ use spl_token::amount_to_ui_amount;
 
 use crate::{
     args::ClaimArgs,
-    send_and_confirm::ComputeBudget,
+    send_and_confirm::{ ComputeBudget, TxOutcome },
     utils::{ ask_confirm, get_proof_with_authority },
     Miner,
     DEFAULT_JITO_TIP,
@@ -36,14 +36,25 @@ impl Miner {
 
         // Claim stake
         if proof.balance.gt(&0) {
-            self.claim(ClaimArgs {
+            let claimed = self.claim(ClaimArgs {
                 amount: None,
                 to: None,
             }).await;
+            if !claimed {
+                return;
+            }
         }
 
         // Submit close transaction
         let ix = ore_api::instruction::close(signer.pubkey());
-        self.send_and_confirm(&[ix], ComputeBudget::Dynamic, DEFAULT_JITO_TIP).await.ok();
+        match self.send_and_confirm(&[ix], ComputeBudget::Dynamic, DEFAULT_JITO_TIP).await {
+            Ok(TxOutcome::Landed(_)) => {}
+            Ok(outcome) => {
+                println!("{} Close did not land: {}", "ERROR".bold().red(), outcome);
+            }
+            Err(err) => {
+                println!("{} {}", "ERROR".bold().red(), err);
+            }
+        }
     }
 }
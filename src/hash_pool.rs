@@ -0,0 +1,348 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+    thread::JoinHandle,
+    time::Instant,
+};
+
+use colored::*;
+use core_affinity::CoreId;
+use crossbeam::channel::{self, Receiver, Sender};
+use drillx::Hash;
+
+use crate::{backend::HashBackend, mine::MineStrategy};
+
+/// A hashing worker: the core it is pinned to and the start of its nonce range.
+#[derive(Clone, Copy)]
+pub struct Worker {
+    pub core_id: CoreId,
+    pub first_nonce: u64,
+}
+
+/// The best hash a worker has found, along with the nonce that produced it and the
+/// number of hashes computed to find it.
+#[derive(Default)]
+pub struct Best {
+    pub difficulty: u32,
+    pub nonce: u64,
+    pub hash: Hash,
+    pub hashes: u64,
+}
+
+impl Best {
+    /// Merges the workers' results into the best overall, counting every worker's hashes.
+    pub fn merge(results: Vec<Best>) -> Best {
+        let hashes = results.iter().map(|worker_best| worker_best.hashes).sum();
+        let mut best = Best::default();
+        for worker_best in results {
+            if worker_best.difficulty > best.difficulty {
+                best = worker_best;
+            }
+        }
+        best.hashes = hashes;
+        best
+    }
+}
+
+/// One round of hashing, sent to every worker in the pool.
+#[derive(Clone)]
+pub struct Job {
+    pub challenge: [u8; 32],
+    pub strategy: MineStrategy,
+    pub target_difficulty: u32,
+    pub min_difficulty: u32,
+    pub deadline: Instant,
+    /// Past this the program cuts the reward, so no strategy hashes beyond it.
+    pub liveness_deadline: Instant,
+    pub global_best_difficulty: Arc<AtomicU32>,
+    /// Set to stop hashing at once, whatever the strategy says.
+    pub stop: Arc<AtomicBool>,
+    /// Called with the new global best whenever a worker improves on it.
+    pub on_improve: Arc<dyn Fn(u32) + Send + Sync>,
+    pub results: Sender<Best>,
+}
+
+/// A fixed set of pinned hashing threads that live for the whole mining session. Each
+/// thread sets up its backend worker once and then waits for jobs on its own channel.
+pub struct HashPool {
+    jobs: Vec<Sender<Job>>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl HashPool {
+    pub fn new(backend: Arc<dyn HashBackend>, workers: &[Worker]) -> Self {
+        let (jobs, threads) = workers
+            .iter()
+            .map(|&worker| {
+                let (tx, rx) = channel::bounded::<Job>(1);
+                let backend = Arc::clone(&backend);
+                (
+                    tx,
+                    std::thread::spawn(move || run_worker(backend, worker, rx)),
+                )
+            })
+            .unzip();
+        Self { jobs, threads }
+    }
+
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    /// Hands the job to every worker. Each worker sends its best hash on `job.results`
+    /// once it stops.
+    pub fn submit(&self, job: &Job) {
+        for jobs in &self.jobs {
+            jobs.send(job.clone()).expect("Hashing thread exited");
+        }
+    }
+}
+
+impl Drop for HashPool {
+    fn drop(&mut self) {
+        // Closing the job channels lets the threads return
+        self.jobs.clear();
+        for thread in self.threads.drain(..) {
+            thread.join().ok();
+        }
+    }
+}
+
+fn run_worker(backend: Arc<dyn HashBackend>, worker: Worker, jobs: Receiver<Job>) {
+    if !core_affinity::set_for_current(worker.core_id) {
+        println!(
+            "{} Could not pin a hashing thread to core {}",
+            "WARNING".bold().yellow(),
+            worker.core_id.id
+        );
+    }
+    let mut hasher = backend.worker();
+    while let Ok(job) = jobs.recv() {
+        // Track this worker's own best so the nonce, digest and difficulty it
+        // returns always belong together
+        let mut best = Best::default();
+        let mut nonce = worker.first_nonce;
+        loop {
+            if job.stop.load(Ordering::Relaxed) {
+                break;
+            }
+            let global_best = job.global_best_difficulty.load(Ordering::Relaxed);
+            let now = Instant::now();
+            if job.strategy.should_stop(
+                global_best,
+                job.target_difficulty,
+                job.min_difficulty,
+                now >= job.deadline,
+                now >= job.liveness_deadline,
+            ) {
+                break;
+            }
+
+            if let Some(hx) = hasher.hash(&job.challenge, nonce) {
+                let difficulty = hx.difficulty();
+                if difficulty > best.difficulty {
+                    best = Best {
+                        difficulty,
+                        nonce,
+                        hash: hx,
+                        hashes: 0,
+                    };
+
+                    // Publish improvements on the global best
+                    let prev = job
+                        .global_best_difficulty
+                        .fetch_max(difficulty, Ordering::Relaxed);
+                    if difficulty > prev {
+                        (job.on_improve)(difficulty);
+                    }
+                }
+            }
+
+            // Increment nonce
+            nonce += 1;
+        }
+        best.hashes = nonce - worker.first_nonce;
+        job.results.send(best).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use drillx::Solution;
+
+    use super::*;
+    use crate::backend::{CpuBackend, ScriptedBackend};
+
+    /// Runs one job on a pool of `threads` workers and merges their results.
+    #[allow(clippy::too_many_arguments)]
+    fn run_job(
+        backend: Arc<dyn HashBackend>,
+        threads: u64,
+        challenge: [u8; 32],
+        strategy: MineStrategy,
+        target_difficulty: u32,
+        min_difficulty: u32,
+        deadline: Instant,
+        liveness_deadline: Instant,
+    ) -> Best {
+        let workers: Vec<Worker> = (0..threads)
+            .map(|i| Worker {
+                core_id: CoreId { id: 0 },
+                first_nonce: i * 1_000_000,
+            })
+            .collect();
+        let pool = HashPool::new(backend, &workers);
+        let (results_tx, results_rx) = channel::unbounded();
+        pool.submit(&Job {
+            challenge,
+            strategy,
+            target_difficulty,
+            min_difficulty,
+            deadline,
+            liveness_deadline,
+            global_best_difficulty: Arc::new(AtomicU32::new(0)),
+            stop: Arc::new(AtomicBool::new(false)),
+            on_improve: Arc::new(|_| {}),
+            results: results_tx,
+        });
+        Best::merge(results_rx.iter().take(pool.len()).collect())
+    }
+
+    fn scripted(difficulties: Vec<u32>) -> Arc<dyn HashBackend> {
+        Arc::new(ScriptedBackend { difficulties })
+    }
+
+    #[test]
+    fn merged_best_is_a_valid_solution() {
+        let challenge = [7; 32];
+        let best = run_job(
+            Arc::new(CpuBackend),
+            2,
+            challenge,
+            MineStrategy::Min,
+            4,
+            1,
+            Instant::now() + Duration::from_secs(60),
+            Instant::now() + Duration::from_secs(70),
+        );
+
+        let solution = Solution::new(best.hash.d, best.nonce.to_le_bytes());
+        assert!(best.difficulty >= 4);
+        assert!(drillx::is_valid_digest(
+            &challenge,
+            &solution.n,
+            &solution.d
+        ));
+        assert_eq!(best.hash.difficulty(), best.difficulty);
+    }
+
+    #[test]
+    fn min_stops_at_target() {
+        let best = run_job(
+            scripted(vec![1, 3, 5, 2, 8]),
+            1,
+            [0; 32],
+            MineStrategy::Min,
+            5,
+            1,
+            Instant::now() + Duration::from_secs(60),
+            Instant::now() + Duration::from_secs(70),
+        );
+        assert_eq!(best.difficulty, 5);
+        assert_eq!(best.nonce, 2);
+        assert_eq!(best.hashes, 3);
+    }
+
+    #[test]
+    fn max_runs_to_deadline() {
+        let started_at = Instant::now();
+        let deadline = started_at + Duration::from_millis(100);
+        let best = run_job(
+            scripted(vec![1, 3, 5, 2, 8]),
+            1,
+            [0; 32],
+            MineStrategy::Max,
+            5,
+            1,
+            deadline,
+            deadline + Duration::from_secs(10),
+        );
+        assert!(Instant::now() >= deadline);
+        assert_eq!(best.difficulty, 8);
+        assert_eq!(best.nonce, 4);
+    }
+
+    #[test]
+    fn never_stops_below_min_difficulty() {
+        let mut difficulties = vec![1; 500];
+        difficulties.push(6);
+        for strategy in [
+            MineStrategy::Min,
+            MineStrategy::Max,
+            MineStrategy::TargetThenMax,
+        ] {
+            let best = run_job(
+                scripted(difficulties.clone()),
+                1,
+                [0; 32],
+                strategy,
+                1,
+                6,
+                Instant::now(),
+                Instant::now(),
+            );
+            assert_eq!(best.difficulty, 6);
+            assert_eq!(best.hashes, 501);
+        }
+    }
+
+    #[test]
+    fn target_then_max_stops_at_liveness_limit() {
+        let started_at = Instant::now();
+        let liveness_deadline = started_at + Duration::from_millis(100);
+        let best = run_job(
+            scripted(vec![1, 3, 2]),
+            1,
+            [0; 32],
+            MineStrategy::TargetThenMax,
+            8,
+            1,
+            started_at,
+            liveness_deadline,
+        );
+        assert!(Instant::now() >= liveness_deadline);
+        assert_eq!(best.difficulty, 3);
+        assert_eq!(best.nonce, 1);
+    }
+
+    #[test]
+    fn merge_keeps_best_and_counts_all_hashes() {
+        let best = Best::merge(vec![
+            Best {
+                difficulty: 3,
+                nonce: 10,
+                hashes: 100,
+                ..Best::default()
+            },
+            Best {
+                difficulty: 7,
+                nonce: 20,
+                hashes: 50,
+                ..Best::default()
+            },
+            Best {
+                difficulty: 5,
+                nonce: 30,
+                hashes: 25,
+                ..Best::default()
+            },
+        ]);
+        assert_eq!(best.difficulty, 7);
+        assert_eq!(best.nonce, 20);
+        assert_eq!(best.hashes, 175);
+    }
+}
@@ -0,0 +1,156 @@
+use std::str::FromStr;
+
+use colored::*;
+use rand::prelude::SliceRandom;
+use serde::Deserialize;
+use serde_json::json;
+use solana_client::{
+    client_error::{ClientErrorKind, Result as ClientResult},
+    nonblocking::rpc_client::RpcClient,
+    rpc_request::RpcRequest,
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    pubkey::Pubkey,
+    transaction::{Transaction, TransactionError},
+};
+use solana_transaction_status::TransactionConfirmationStatus;
+
+/// The default Jito block engine.
+pub const DEFAULT_BLOCK_ENGINE_URL: &str = "https://mainnet.block-engine.jito.wtf";
+
+/// The block engine path that serves the bundle JSON-RPC methods.
+const BUNDLES_PATH: &str = "/api/v1/bundles";
+
+/// Tip accounts to use if the block engine cannot be asked for its own.
+const DEFAULT_TIP_ACCOUNTS: [&str; 8] = [
+    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
+    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
+    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
+    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
+    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
+];
+
+/// The status of a landed bundle, as reported by `getBundleStatuses`.
+#[derive(Debug, Deserialize)]
+pub struct BundleStatus {
+    pub confirmation_status: TransactionConfirmationStatus,
+    pub err: Result<(), TransactionError>,
+}
+
+impl BundleStatus {
+    pub fn satisfies_commitment(&self, commitment: CommitmentConfig) -> bool {
+        match self.confirmation_status {
+            TransactionConfirmationStatus::Finalized => true,
+            TransactionConfirmationStatus::Confirmed => !commitment.is_finalized(),
+            TransactionConfirmationStatus::Processed => {
+                !commitment.is_finalized() && !commitment.is_confirmed()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BundleStatuses {
+    value: Vec<Option<BundleStatus>>,
+}
+
+/// A client for the bundle endpoints of a Jito block engine, along with the tip
+/// accounts that bundles pay into.
+pub struct JitoClient {
+    client: RpcClient,
+    tip_accounts: Vec<Pubkey>,
+}
+
+impl JitoClient {
+    /// Connects to the block engine at `url` and loads its tip accounts from the given
+    /// file, the block engine itself, or the built-in defaults, in that order.
+    pub async fn new(url: &str, tip_accounts_file: Option<&str>) -> Result<Self, String> {
+        let client = RpcClient::new(format!("{}{}", url.trim_end_matches('/'), BUNDLES_PATH));
+        let tip_accounts = match tip_accounts_file {
+            Some(path) => {
+                let data = std::fs::read_to_string(path).map_err(|err| {
+                    format!("Could not read tip accounts file `{}`: {}", path, err)
+                })?;
+                serde_json::from_str::<Vec<String>>(&data).map_err(|err| {
+                    format!("Could not parse tip accounts file `{}`: {}", path, err)
+                })?
+            }
+            None => client
+                .send::<Vec<String>>(
+                    RpcRequest::Custom {
+                        method: "getTipAccounts",
+                    },
+                    json!([]),
+                )
+                .await
+                .unwrap_or_else(|err| {
+                    println!(
+                        "{} Could not fetch tip accounts from the block engine ({}), using the defaults",
+                        "WARNING".bold().yellow(),
+                        err
+                    );
+                    DEFAULT_TIP_ACCOUNTS.map(String::from).to_vec()
+                }),
+        };
+
+        // Validate the tip accounts once, up front
+        let tip_accounts = tip_accounts
+            .iter()
+            .map(|address| {
+                Pubkey::from_str(address)
+                    .map_err(|_| format!("Invalid tip account address `{}`", address))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if tip_accounts.is_empty() {
+            return Err("No tip accounts available".to_string());
+        }
+
+        Ok(Self {
+            client,
+            tip_accounts,
+        })
+    }
+
+    /// Returns a random tip account.
+    pub fn tip_account(&self) -> Pubkey {
+        *self
+            .tip_accounts
+            .choose(&mut rand::thread_rng())
+            .expect("Tip accounts are validated to be non-empty")
+    }
+
+    /// Submits the transactions as an atomic bundle and returns the bundle id.
+    pub async fn send_bundle(&self, txs: &[&Transaction]) -> ClientResult<String> {
+        let encoded = txs
+            .iter()
+            .map(|tx| Ok(bs58::encode(bincode::serialize(tx)?).into_string()))
+            .collect::<Result<Vec<_>, bincode::Error>>()
+            .map_err(|err| ClientErrorKind::Custom(err.to_string()))?;
+        self.client
+            .send(
+                RpcRequest::Custom {
+                    method: "sendBundle",
+                },
+                json!([encoded]),
+            )
+            .await
+    }
+
+    /// Fetches the status of a bundle, or `None` if it has not landed.
+    pub async fn get_bundle_status(&self, bundle_id: &str) -> ClientResult<Option<BundleStatus>> {
+        let statuses: BundleStatuses = self
+            .client
+            .send(
+                RpcRequest::Custom {
+                    method: "getBundleStatuses",
+                },
+                json!([[bundle_id]]),
+            )
+            .await?;
+        Ok(statuses.value.into_iter().next().flatten())
+    }
+}
@@ -1,25 +1,40 @@
 mod args;
+mod backend;
 mod balance;
 mod benchmark;
+mod bus_strategy;
 mod busses;
 mod claim;
 mod close;
 mod config;
 mod cu_limits;
+mod hash_pool;
+mod jito;
 mod mine;
 mod open;
+mod priority_fee;
+mod retry;
 mod rewards;
 mod send_and_confirm;
+mod send_pool;
+mod session;
+mod shutdown;
 mod stake;
+mod tip_stream;
 mod upgrade;
 mod utils;
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 pub const DEFAULT_JITO_TIP: u64 = 5000;
 
 use args::*;
 use clap::{command, Parser, Subcommand};
+use jito::JitoClient;
+use priority_fee::{PriorityFeeConfig, PriorityFeeStrategy};
+use retry::RetryPolicy;
+use send_pool::SendPool;
+use utils::Commitment;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
@@ -28,10 +43,13 @@ use solana_sdk::{
 
 struct Miner {
     pub keypair_filepath: Option<String>,
-    pub priority_fee: u64,
+    pub priority_fee: PriorityFeeConfig,
     pub rpc_client: Arc<RpcClient>,
-    pub send_client: Arc<RpcClient>,
-    pub jito: bool,
+    pub write_client: Arc<RpcClient>,
+    pub send_pool: SendPool,
+    pub jito: Option<JitoClient>,
+    pub retry_policy: RetryPolicy,
+    pub compute_unit_margin: u64,
 }
 
 #[derive(Subcommand, Debug)]
@@ -78,6 +96,50 @@ struct Args {
     )]
     rpc: Option<String>,
 
+    #[arg(
+        long,
+        value_name = "NETWORK_URL",
+        help = "RPC provider to broadcast transactions to. Repeat to send through several providers at once. Defaults to --rpc.",
+        global = true
+    )]
+    send_rpc: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "NETWORK_URL",
+        help = "RPC provider for account reads. Defaults to --rpc.",
+        global = true
+    )]
+    read_rpc: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "COMMITMENT",
+        help = "Commitment level for account reads",
+        default_value = "confirmed",
+        global = true
+    )]
+    read_commitment: Commitment,
+
+    #[arg(
+        long,
+        value_name = "NETWORK_URL",
+        help = "RPC provider for blockhashes, simulations and confirmations. Defaults to --rpc.",
+        global = true
+    )]
+    write_rpc: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "COMMITMENT",
+        help = "Commitment level for blockhashes and transaction confirmations",
+        default_value = "confirmed",
+        global = true
+    )]
+    write_commitment: Commitment,
+
     #[clap(
         global = true,
         short = 'C',
@@ -104,9 +166,100 @@ struct Args {
     )]
     priority_fee: u64,
 
+    #[arg(
+        long,
+        value_enum,
+        value_name = "STRATEGY",
+        help = "How to price transactions: a static fee, or one derived from recent prioritization fees",
+        default_value = "static",
+        global = true
+    )]
+    priority_fee_strategy: PriorityFeeStrategy,
+
+    #[arg(
+        long,
+        value_name = "PERCENTILE",
+        help = "Percentile of recent prioritization fees to pay with the dynamic strategy",
+        default_value = "75",
+        value_parser = clap::value_parser!(u8).range(0..=100),
+        global = true
+    )]
+    priority_fee_percentile: u8,
+
+    #[arg(
+        long,
+        value_name = "MICROLAMPORTS",
+        help = "Lower bound on the priority fee chosen by the dynamic strategy",
+        default_value = "0",
+        global = true
+    )]
+    priority_fee_min: u64,
+
+    #[arg(
+        long,
+        value_name = "MICROLAMPORTS",
+        help = "Upper bound on the priority fee chosen by the dynamic strategy",
+        default_value = "500000",
+        global = true
+    )]
+    priority_fee_max: u64,
+
     #[arg(long, value_name = "JITO", help = "USE JITO", global = true)]
     jito: bool,
 
+    #[arg(
+        long,
+        value_name = "URL",
+        help = "Jito block engine to submit bundles to",
+        default_value = jito::DEFAULT_BLOCK_ENGINE_URL,
+        global = true
+    )]
+    jito_url: String,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "JSON file listing the Jito tip accounts to pay. Defaults to asking the block engine.",
+        global = true
+    )]
+    jito_tip_accounts: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "COUNT",
+        help = "Maximum number of attempts for a failing RPC request",
+        default_value = "8",
+        global = true
+    )]
+    max_retries: usize,
+
+    #[arg(
+        long,
+        value_name = "MILLISECONDS",
+        help = "Initial delay between retries of a failing RPC request, doubled on each attempt",
+        default_value = "500",
+        global = true
+    )]
+    retry_backoff: u64,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Total time to spend retrying a failing RPC request before giving up",
+        default_value = "60",
+        global = true
+    )]
+    retry_deadline: u64,
+
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        help = "Safety margin added to simulated compute units for dynamically budgeted transactions",
+        default_value = "10",
+        global = true
+    )]
+    compute_unit_margin: u64,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -130,16 +283,57 @@ async fn main() {
     // Initialize miner.
     let cluster = args.rpc.unwrap_or(cli_config.json_rpc_url);
     let default_keypair = args.keypair.unwrap_or(cli_config.keypair_path);
-    let rpc_client = RpcClient::new_with_commitment(cluster, CommitmentConfig::confirmed());
-    let send_client =
-        RpcClient::new("https://mainnet.block-engine.jito.wtf/api/v1/transactions".to_string());
+    let read_commitment = CommitmentConfig::from(args.read_commitment);
+    let write_commitment = CommitmentConfig::from(args.write_commitment);
+    let rpc_client = Arc::new(RpcClient::new_with_commitment(
+        args.read_rpc.unwrap_or(cluster.clone()),
+        read_commitment,
+    ));
+    let write_client = Arc::new(RpcClient::new_with_commitment(
+        args.write_rpc.unwrap_or(cluster),
+        write_commitment,
+    ));
+    let send_pool = if args.send_rpc.is_empty() {
+        SendPool::new(vec![write_client.clone()])
+    } else {
+        SendPool::new(
+            args.send_rpc
+                .into_iter()
+                .map(|url| Arc::new(RpcClient::new_with_commitment(url, write_commitment)))
+                .collect(),
+        )
+    };
+    let jito = if args.jito {
+        let jito = JitoClient::new(&args.jito_url, args.jito_tip_accounts.as_deref())
+            .await
+            .unwrap_or_else(|err| {
+                eprintln!("error: {}", err);
+                std::process::exit(1);
+            });
+        Some(jito)
+    } else {
+        None
+    };
 
     let miner = Arc::new(Miner::new(
-        Arc::new(rpc_client),
-        args.priority_fee,
+        rpc_client,
+        write_client,
+        PriorityFeeConfig {
+            strategy: args.priority_fee_strategy,
+            fee: args.priority_fee,
+            percentile: args.priority_fee_percentile,
+            min: args.priority_fee_min,
+            max: args.priority_fee_max,
+        },
         Some(default_keypair),
-        Arc::new(send_client),
-        args.jito,
+        send_pool,
+        jito,
+        RetryPolicy {
+            max_attempts: args.max_retries,
+            base_delay: Duration::from_millis(args.retry_backoff),
+            deadline: Duration::from_secs(args.retry_deadline),
+        },
+        args.compute_unit_margin,
     ));
 
     // Execute user command.
@@ -178,26 +372,33 @@ async fn main() {
 }
 
 impl Miner {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         rpc_client: Arc<RpcClient>,
-        priority_fee: u64,
+        write_client: Arc<RpcClient>,
+        priority_fee: PriorityFeeConfig,
         keypair_filepath: Option<String>,
-        send_client: Arc<RpcClient>,
-        jito: bool,
+        send_pool: SendPool,
+        jito: Option<JitoClient>,
+        retry_policy: RetryPolicy,
+        compute_unit_margin: u64,
     ) -> Self {
         Self {
             rpc_client,
+            write_client,
             keypair_filepath,
             priority_fee,
-            send_client,
+            send_pool,
             jito,
+            retry_policy,
+            compute_unit_margin,
         }
     }
 
     pub fn signer(&self) -> Keypair {
         match self.keypair_filepath.clone() {
             Some(filepath) => read_keypair_file(filepath.clone())
-                .expect(format!("No keypair found at {}", filepath).as_str()),
+                .unwrap_or_else(|_| panic!("No keypair found at {}", filepath)),
             None => panic!("No keypair provided"),
         }
     }
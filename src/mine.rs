@@ -1,86 +1,151 @@
+use clap::ValueEnum;
 use colored::*;
-use drillx::{equix, Hash, Solution};
-use futures::StreamExt;
+use core_affinity::CoreId;
+use drillx::Solution;
 use ore_api::{
-    consts::{BUS_ADDRESSES, BUS_COUNT},
-    state::{Bus, Proof},
+    consts::{BUS_COUNT, ONE_MINUTE, TOLERANCE},
+    state::Proof,
 };
 use ore_utils::AccountDeserialize;
-use rand::Rng;
-use rayon::prelude::*;
-use solana_program::pubkey::Pubkey;
 use solana_rpc_client::spinner;
 use solana_sdk::signer::Signer;
-use std::sync::atomic::AtomicU32;
 use std::sync::{
-    atomic::{AtomicU64, Ordering},
+    atomic::{AtomicU32, Ordering},
     Arc, Mutex,
 };
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
-use tokio_tungstenite::connect_async;
-use tokio_tungstenite::tungstenite::protocol::Message;
 
 use crate::{
     args::MineArgs,
-    send_and_confirm::ComputeBudget,
-    utils::{
-        amount_u64_to_string, get_clock, get_config, get_proof_with_authority, proof_pubkey, Tip,
-    },
+    bus_strategy::{expected_reward, BusSelector},
+    hash_pool::{Best, HashPool, Job, Worker},
+    send_and_confirm::{ComputeBudget, TxOutcome},
+    session::{MiningSession, Round},
+    shutdown::Shutdown,
+    tip_stream::{TipStream, TipStreamConfig},
+    utils::{amount_u64_to_string, get_clock, get_config, get_proof_with_authority, proof_pubkey},
     Miner,
 };
-use crossbeam::channel;
 
 impl Miner {
     pub async fn mine(&self, args: MineArgs) {
         let signer = self.signer();
 
-        self.open().await;
-
-        let tip = Arc::new(RwLock::new(0_u64));
-        let tip_clone = Arc::clone(&tip);
-
-        if self.jito {
-            let url = "ws://bundles-api-rest.jito.wtf/api/v1/bundles/tip_stream";
-            let (ws_stream, _) = connect_async(url).await.unwrap();
-            let (_, mut read) = ws_stream.split();
-
-            tokio::spawn(async move {
-                while let Some(message) = read.next().await {
-                    if let Ok(Message::Text(text)) = message {
-                        if let Ok(tips) = serde_json::from_str::<Vec<Tip>>(&text) {
-                            for item in tips {
-                                let mut tip = tip_clone.write().await;
-                                *tip =
-                                    (item.landed_tips_50th_percentile * (10_f64).powf(9.0)) as u64;
-                            }
-                        }
-                    }
-                }
-            });
+        // Assign each worker thread the core it will be pinned to
+        let available: Vec<usize> = core_affinity::get_core_ids()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|core_id| core_id.id)
+            .collect();
+        let cores = match &args.cores {
+            Some(cores) => parse_core_list(cores)
+                .and_then(
+                    |list| match list.iter().find(|core| !available.contains(core)) {
+                        Some(core) => Err(format!(
+                            "core {} is not available (available: {:?})",
+                            core, available
+                        )),
+                        None => Ok(list),
+                    },
+                )
+                .unwrap_or_else(|err| {
+                    eprintln!("error: Invalid core list `{}`: {}", cores, err);
+                    std::process::exit(1);
+                }),
+            None if available.is_empty() => {
+                eprintln!("error: Could not list the available cores");
+                std::process::exit(1);
+            }
+            None => available,
+        };
+        let threads = args.threads.unwrap_or(cores.len() as u64);
+        self.check_num_cores(threads);
+        if args.nonce_offset >= args.nonce_stride {
+            eprintln!(
+                "error: --nonce-offset ({}) must be less than --nonce-stride ({})",
+                args.nonce_offset, args.nonce_stride
+            );
+            std::process::exit(1);
         }
+        let workers: Vec<Worker> = (0..threads)
+            .map(|i| Worker {
+                core_id: CoreId {
+                    id: cores[i as usize % cores.len()],
+                },
+                first_nonce: first_nonce(args.nonce_offset, args.nonce_stride, threads, i),
+            })
+            .collect();
+
+        let pool = HashPool::new(args.backend.build(), &workers);
+        let shutdown = Shutdown::listen();
+        let mut bus_selector = BusSelector::new(args.bus_strategy);
+
+        self.open().await;
 
-        let core_ids = core_affinity::get_core_ids().unwrap();
+        let tip_stream = self.jito.is_some().then(|| {
+            TipStream::spawn(TipStreamConfig {
+                url: args.tip_stream_url.clone(),
+                percentile: args.tip_percentile,
+                multiplier: args.tip_multiplier,
+                min: args.tip_min,
+                max: args.tip_max,
+                stale_after: Duration::from_secs(args.tip_stale_after),
+                fallback: args.tip_fallback,
+            })
+        });
 
         println!(
             "{}",
-            format!("Mining with {} threads", core_ids.len())
+            format!("Mining with {} threads", workers.len())
                 .bold()
                 .green()
         );
 
-        loop {
-            let proof = get_proof_with_authority(&self.rpc_client, signer.pubkey()).await;
+        let session = Arc::new(Mutex::new(MiningSession::new()));
+        MiningSession::print_on_signal(Arc::clone(&session));
+        let mut last_proof: Option<Proof> = None;
+        while !shutdown.is_requested() {
+            let mut proof = get_proof_with_authority(&self.rpc_client, signer.pubkey()).await;
+
+            // Report what the last submission earned, if it landed, and carry on from
+            // the freshest proof seen so the next round hashes on the current challenge
+            if let Some(last_proof) = last_proof {
+                let confirmed = session.lock().unwrap().last_round_landed();
+                let (reward, fresh) = self.settle_reward(&last_proof, &proof, confirmed).await;
+                proof = fresh;
+                let mut session = session.lock().unwrap();
+                match reward {
+                    Some(reward) => {
+                        println!(
+                            "\nEarned: {} ORE",
+                            amount_u64_to_string(reward).bold().green()
+                        );
+                        let expected = session.last_round_expected_reward();
+                        if reward < expected {
+                            println!(
+                                "{} Bus could not cover the full reward: earned {} of the {} ORE expected",
+                                "WARNING".bold().yellow(),
+                                amount_u64_to_string(reward),
+                                amount_u64_to_string(expected)
+                            );
+                        }
+                    }
+                    None if session.last_round_submitted() => println!(
+                        "\n{} Proof did not advance, so the last submission did not land",
+                        "WARNING".bold().yellow()
+                    ),
+                    None => {}
+                }
+                session.settle_last_round(reward);
+            }
+            last_proof = Some(proof);
 
             let config = get_config(&self.rpc_client).await;
 
             println!(
                 "\nHashes: {} Rewards: {}",
                 proof.total_hashes.to_string().bold().blue(),
-                (proof.total_rewards as f64 / 10u64.pow(11) as f64)
-                    .to_string()
-                    .bold()
-                    .blue()
+                amount_u64_to_string(proof.total_rewards).bold().blue()
             );
 
             println!(
@@ -94,118 +159,271 @@ impl Miner {
                 .green()
             );
 
-            let solution = Self::find_hash_par(&self, proof, args.diff).await;
+            // Hash right up to the cutoff, less the buffer needed to submit
+            let cutoff_time = self.get_cutoff(proof, args.buffer_time).await;
+            let deadline = Instant::now() + Duration::from_secs(cutoff_time);
+            let liveness_time = self.get_liveness_cutoff(proof).await;
+            let liveness_deadline = Instant::now() + Duration::from_secs(liveness_time);
+            let best = Self::find_hash_par(
+                self,
+                proof,
+                args.strategy,
+                args.diff,
+                config.min_difficulty as u32,
+                &pool,
+                deadline,
+                liveness_deadline,
+                &shutdown,
+            )
+            .await;
+            let solution = Solution::new(best.hash.d, best.nonce.to_le_bytes());
+            let mut round = Round {
+                difficulty: best.difficulty,
+                hashes: best.hashes,
+                ..Round::default()
+            };
+
+            // On shutdown, only submit what was found so far if asked to
+            let stopping = shutdown.is_requested();
+            if stopping && !args.submit_on_exit {
+                session.lock().unwrap().record(round);
+                break;
+            }
 
-            let mut ixs = vec![];
+            // Skip solutions the program would reject
+            let config = get_config(&self.rpc_client).await;
+            let difficulty =
+                match verify_solution(&solution, &proof.challenge, config.min_difficulty as u32) {
+                    Ok(difficulty) => difficulty,
+                    Err(reason) => {
+                        println!(
+                            "{} Skipping submission: {}",
+                            "WARNING".bold().yellow(),
+                            reason
+                        );
+                        session.lock().unwrap().record(round);
+                        if stopping {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+
+            // Submitting before the spam threshold would fail, so hold the solution
+            // until then. Signalling again exits without submitting.
+            if stopping {
+                let wait = self.get_cutoff(proof, TOLERANCE as u64).await;
+                if wait > 0 {
+                    println!(
+                        "{} Hashing stopped. Waiting {} sec for the spam threshold before submitting...",
+                        "INFO".bold().blue(),
+                        wait
+                    );
+                    tokio::time::sleep(Duration::from_secs(wait)).await;
+                }
+            }
 
-            let current_tip = *tip.read().await;
+            let current_tip = match &tip_stream {
+                Some(tip_stream) => tip_stream.current().await,
+                None => 0,
+            };
 
-            ixs.push(ore_api::instruction::auth(proof_pubkey(signer.pubkey())));
+            // Submit, moving to the next best bus whenever the chosen one runs dry
+            let expected_reward = expected_reward(
+                &config,
+                difficulty,
+                calculate_multiplier(proof.balance, config.top_balance),
+            );
+            let sol_balance = self.rpc_client.get_balance(&signer.pubkey()).await.ok();
+            let mut exhausted = vec![];
+            loop {
+                let bus = bus_selector
+                    .select(&self.rpc_client, expected_reward, &exhausted)
+                    .await;
+                let ixs = vec![
+                    ore_api::instruction::auth(proof_pubkey(signer.pubkey())),
+                    ore_api::instruction::mine(signer.pubkey(), signer.pubkey(), bus, solution),
+                ];
+                let can_retarget = exhausted.len() + 1 < BUS_COUNT;
+                if can_retarget && self.is_bus_exhausted(&ixs, bus).await {
+                    println!(
+                        "{} Bus {} cannot cover the reward, retargeting",
+                        "WARNING".bold().yellow(),
+                        bus
+                    );
+                    exhausted.push(bus);
+                    continue;
+                }
 
-            ixs.push(ore_api::instruction::mine(
-                signer.pubkey(),
-                signer.pubkey(),
-                self.find_bus().await,
-                solution,
-            ));
+                let result = self
+                    .send_and_confirm(&ixs, ComputeBudget::Fixed(500_000), current_tip)
+                    .await;
+                if let Ok(outcome) = &result {
+                    round.signature = Some(outcome.signature());
+                }
+                match result {
+                    Ok(TxOutcome::Landed(_)) => {
+                        round.landed = true;
+                        round.expected_reward = expected_reward;
+                        if self.jito.is_some() {
+                            round.tip = current_tip;
+                        }
+                        if !stopping {
+                            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                        }
+                    }
+                    Ok(outcome) => {
+                        println!(
+                            "{} Solution did not land: {}",
+                            "WARNING".bold().yellow(),
+                            outcome
+                        );
+                    }
+                    Err(err) => {
+                        println!("{} {}", "ERROR".bold().red(), err);
+                    }
+                }
+                break;
+            }
 
-            self.send_and_confirm(&ixs, ComputeBudget::Fixed(500_000), current_tip.clone())
-                .await
-                .ok();
+            // Whatever left the wallet besides the tip went to transaction fees
+            if let (Some(before), Ok(after)) = (
+                sol_balance,
+                self.rpc_client.get_balance(&signer.pubkey()).await,
+            ) {
+                round.fees = before.saturating_sub(after).saturating_sub(round.tip);
+            }
+            session.lock().unwrap().record(round);
+        }
 
-            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+        // Settle the final round before summarizing
+        if let Some(last_proof) = last_proof {
+            let proof = get_proof_with_authority(&self.rpc_client, signer.pubkey()).await;
+            let confirmed = session.lock().unwrap().last_round_landed();
+            let (reward, _) = self.settle_reward(&last_proof, &proof, confirmed).await;
+            session.lock().unwrap().settle_last_round(reward);
         }
+        println!("\n{}", session.lock().unwrap().summary());
     }
 
-    async fn find_hash_par(&self, proof: Proof, min_difficulty: u32) -> Solution {
-        let progress_bar = Arc::new(spinner::new_progress_bar());
-        let best_difficulty = Arc::new(AtomicU32::new(0));
-        let best_nonce = Arc::new(AtomicU64::new(0));
-        let best_hash = Arc::new(Mutex::new(Hash::default()));
-        let (sender, _receiver) = channel::unbounded();
-        let timeout = Duration::from_secs(45);
-        let start_time = Instant::now();
-        let rt = tokio::runtime::Handle::current();
-        let core_ids = core_affinity::get_core_ids().unwrap();
-
-        let handles: Vec<_> = core_ids
-            .into_par_iter()
-            .map(|core_id| {
-                let proof = proof.clone();
-                let best_difficulty = Arc::clone(&best_difficulty);
-                let best_nonce = Arc::clone(&best_nonce);
-                let best_hash = Arc::clone(&best_hash);
-                let progress_bar = Arc::clone(&progress_bar);
-
-                let sender = sender.clone();
-
-                let mut memory = equix::SolverMemory::new();
-                rt.spawn_blocking(move || loop {
-                    if best_difficulty.load(Ordering::Relaxed) >= min_difficulty
-                        || start_time.elapsed() > timeout
-                    {
-                        let _ = sender.send(());
-                        break;
+    /// Returns the reward the last submission earned, or `None` if it did not land.
+    /// When confirmation reported the submission landed but the read RPC's proof has
+    /// not caught up, the proof is polled from the write RPC at the commitment that
+    /// confirmed it before the round is written off. Also returns the freshest proof
+    /// seen, which the next round must hash on.
+    async fn settle_reward(
+        &self,
+        last_proof: &Proof,
+        proof: &Proof,
+        confirmed: bool,
+    ) -> (Option<u64>, Proof) {
+        let reward = proof_reward(last_proof, proof);
+        if reward.is_some() || !confirmed {
+            return (reward, *proof);
+        }
+        let address = proof_pubkey(self.signer().pubkey());
+        for _ in 0..PROOF_SETTLE_ATTEMPTS {
+            if let Ok(response) = self
+                .write_client
+                .get_account_with_commitment(&address, self.write_client.commitment())
+                .await
+            {
+                let fresh = response
+                    .value
+                    .and_then(|account| Proof::try_from_bytes(&account.data).ok().copied());
+                if let Some(fresh) = fresh {
+                    if let Some(reward) = proof_reward(last_proof, &fresh) {
+                        return (Some(reward), fresh);
                     }
+                }
+            }
+            tokio::time::sleep(PROOF_SETTLE_DELAY).await;
+        }
+        (None, *proof)
+    }
 
-                    let mut rng = rand::thread_rng();
-
-                    let nonce: u64 = core_id.id as u64 * rng.gen_range(0..u64::MAX);
-
-                    if let Ok(hx) = drillx::hash_with_memory(
-                        &mut memory,
-                        &proof.challenge,
-                        &nonce.to_le_bytes(),
-                    ) {
-                        let difficulty = hx.difficulty();
-                        let current_best = best_difficulty.load(Ordering::Relaxed);
-
-                        if difficulty > current_best {
-                            best_nonce.store(nonce, Ordering::Relaxed);
-                            best_difficulty.store(difficulty, Ordering::Relaxed);
-
-                            let mut bh = best_hash.lock().unwrap();
-                            *bh = hx;
-
-                            progress_bar.set_message(format!(
-                                "Difficulty: {}",
-                                format!("{:?}", difficulty).bold().green()
-                            ));
-                        }
-                    }
+    #[allow(clippy::too_many_arguments)]
+    async fn find_hash_par(
+        &self,
+        proof: Proof,
+        strategy: MineStrategy,
+        target_difficulty: u32,
+        min_difficulty: u32,
+        pool: &HashPool,
+        deadline: Instant,
+        liveness_deadline: Instant,
+        shutdown: &Shutdown,
+    ) -> Best {
+        let progress_bar = Arc::new(spinner::new_progress_bar());
+        let global_best_difficulty = Arc::new(AtomicU32::new(0));
+
+        let (results_tx, results_rx) = crossbeam::channel::unbounded();
+        pool.submit(&Job {
+            challenge: proof.challenge,
+            strategy,
+            target_difficulty,
+            min_difficulty,
+            deadline,
+            liveness_deadline,
+            global_best_difficulty: Arc::clone(&global_best_difficulty),
+            stop: shutdown.flag(),
+            on_improve: {
+                let progress_bar = Arc::clone(&progress_bar);
+                Arc::new(move |difficulty| {
+                    progress_bar.set_message(mining_status(difficulty, deadline))
                 })
-            })
-            .collect();
+            },
+            results: results_tx,
+        });
+        let threads = pool.len();
+        let handle = tokio::task::spawn_blocking(move || {
+            results_rx.iter().take(threads).collect::<Vec<Best>>()
+        });
+
+        // Refresh the countdown while the workers hash
+        tokio::pin!(handle);
+        let results = loop {
+            tokio::select! {
+                results = &mut handle => break results,
+                _ = tokio::time::sleep(Duration::from_secs(1)) => {
+                    let difficulty = global_best_difficulty.load(Ordering::Relaxed);
+                    progress_bar.set_message(mining_status(difficulty, deadline));
+                }
+            }
+        };
 
-        for handle in handles {
-            if let Err(err) = handle.await {
+        // Merge the workers' results
+        let best = match results {
+            Ok(results) => Best::merge(results),
+            Err(err) => {
                 eprintln!("Thread error: {:?}", err);
+                Best::default()
             }
-        }
-
-        let final_best_hash = best_hash.lock().unwrap();
-        let final_best_nonce = best_nonce.load(Ordering::Relaxed);
-        let final_best_difficulty = best_difficulty.load(Ordering::Relaxed);
-
-        let cutoff_time = self.get_cutoff(proof).await;
-
-        let mut cutt = cutoff_time;
-
-        while cutt > 0 {
-            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-            cutt -= 1;
+        };
+        let final_best_difficulty = best.difficulty;
 
+        // Wait out the rest of the window if hashing stopped early
+        while Instant::now() < deadline && !shutdown.is_requested() {
             progress_bar.set_message(format!(
-                "Best Difficult: {} - Waiting {} sec to send",
-                format!("{:?}", best_difficulty).bold().green(),
-                format!("{:?}", cutt).bold().green()
-            ))
+                "Best difficulty: {} - Waiting {} sec to send",
+                format!("{:?}", final_best_difficulty).bold().green(),
+                format!(
+                    "{:?}",
+                    deadline.saturating_duration_since(Instant::now()).as_secs()
+                )
+                .bold()
+                .green()
+            ));
+            tokio::time::sleep(
+                Duration::from_secs(1).min(deadline.saturating_duration_since(Instant::now())),
+            )
+            .await;
         }
 
-        if final_best_difficulty < min_difficulty {
+        if final_best_difficulty < target_difficulty {
             println!(
                 "{}",
-                format!("The min difficulty not reached: {}", min_difficulty)
+                format!("The min difficulty not reached: {}", target_difficulty)
                     .bold()
                     .red()
             );
@@ -213,11 +431,11 @@ impl Miner {
 
         progress_bar.finish_with_message(format!(
             "Best hash: {} (difficulty: {})",
-            bs58::encode(final_best_hash.h).into_string(),
+            bs58::encode(best.hash.h).into_string(),
             final_best_difficulty
         ));
 
-        Solution::new(final_best_hash.d, final_best_nonce.to_le_bytes())
+        best
     }
 
     pub fn check_num_cores(&self, threads: u64) {
@@ -232,40 +450,154 @@ impl Miner {
         }
     }
 
-    async fn get_cutoff(&self, proof: Proof) -> u64 {
+    /// Returns the seconds left until `buffer_time` before the one-minute mark. The
+    /// program accepts solutions from `TOLERANCE` seconds before that mark, so buffers
+    /// up to `TOLERANCE` never submit too early.
+    async fn get_cutoff(&self, proof: Proof, buffer_time: u64) -> u64 {
         let clock = get_clock(&self.rpc_client).await;
         proof
             .last_hash_at
-            .saturating_add(60)
-            .saturating_sub(0)
+            .saturating_add(ONE_MINUTE)
+            .saturating_sub(buffer_time as i64)
             .saturating_sub(clock.unix_timestamp)
             .max(0) as u64
     }
 
-    async fn find_bus(&self) -> Pubkey {
-        // Fetch the bus with the largest balance
-        if let Ok(accounts) = self.rpc_client.get_multiple_accounts(&BUS_ADDRESSES).await {
-            let mut top_bus_balance: u64 = 0;
-            let mut top_bus = BUS_ADDRESSES[0];
-            for account in accounts {
-                if let Some(account) = account {
-                    if let Ok(bus) = Bus::try_from_bytes(&account.data) {
-                        if bus.rewards.gt(&top_bus_balance) {
-                            top_bus_balance = bus.rewards;
-                            top_bus = BUS_ADDRESSES[bus.id as usize];
-                        }
-                    }
+    /// Returns the seconds left until the liveness limit, `TOLERANCE` seconds after the
+    /// one-minute mark. Solutions that land past it have their reward cut.
+    async fn get_liveness_cutoff(&self, proof: Proof) -> u64 {
+        let clock = get_clock(&self.rpc_client).await;
+        proof
+            .last_hash_at
+            .saturating_add(ONE_MINUTE)
+            .saturating_add(TOLERANCE)
+            .saturating_sub(clock.unix_timestamp)
+            .max(0) as u64
+    }
+}
+
+/// How many times, and how often, the write RPC is asked for a proof that shows a
+/// confirmed submission.
+const PROOF_SETTLE_ATTEMPTS: usize = 5;
+const PROOF_SETTLE_DELAY: Duration = Duration::from_secs(1);
+
+/// Formats the progress message shown while hashing.
+fn mining_status(best_difficulty: u32, deadline: Instant) -> String {
+    format!(
+        "Best difficulty: {} - Submitting in {} sec",
+        format!("{:?}", best_difficulty).bold().green(),
+        format!(
+            "{:?}",
+            deadline.saturating_duration_since(Instant::now()).as_secs()
+        )
+        .bold()
+        .green()
+    )
+}
+
+/// Recomputes the hash for the solution's nonce and checks that it matches the digest
+/// and meets the minimum difficulty. Returns the solution's difficulty.
+fn verify_solution(
+    solution: &Solution,
+    challenge: &[u8; 32],
+    min_difficulty: u32,
+) -> Result<u32, String> {
+    let hx = drillx::hash(challenge, &solution.n)
+        .map_err(|err| format!("nonce does not produce a hash ({:?})", err))?;
+    if hx.d != solution.d || !drillx::is_valid_digest(challenge, &solution.n, &solution.d) {
+        return Err("digest does not match the nonce".to_string());
+    }
+    let difficulty = hx.difficulty();
+    if difficulty < min_difficulty {
+        return Err(format!(
+            "difficulty {} is below the minimum of {}",
+            difficulty, min_difficulty
+        ));
+    }
+    Ok(difficulty)
+}
+
+/// Returns the reward earned between two reads of a proof, or `None` if no solution
+/// was accepted in between.
+fn proof_reward(prev: &Proof, next: &Proof) -> Option<u64> {
+    let advanced = next.last_hash != prev.last_hash || next.last_hash_at != prev.last_hash_at;
+    advanced.then(|| next.total_rewards.saturating_sub(prev.total_rewards))
+}
+
+fn calculate_multiplier(balance: u64, top_balance: u64) -> f64 {
+    1.0 + (balance as f64 / top_balance as f64).min(1.0f64)
+}
+
+/// Parses a comma separated list of core ids and inclusive ranges, e.g. `0-7,16-23`.
+fn parse_core_list(list: &str) -> Result<Vec<usize>, String> {
+    let mut cores = vec![];
+    for part in list.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let start: usize = start
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("bad core `{}`", start))?;
+                let end: usize = end
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("bad core `{}`", end))?;
+                if start > end {
+                    return Err(format!("bad range `{}`", part));
                 }
+                cores.extend(start..=end);
             }
-            return top_bus;
+            None => cores.push(part.parse().map_err(|_| format!("bad core `{}`", part))?),
         }
+    }
+    if cores.is_empty() {
+        return Err("no cores given".to_string());
+    }
+    Ok(cores)
+}
 
-        // Otherwise return a random bus
-        let i = rand::thread_rng().gen_range(0..BUS_COUNT);
-        BUS_ADDRESSES[i]
+/// When the hashing workers stop searching for a better solution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum MineStrategy {
+    /// Stop as soon as the target difficulty is reached.
+    Min,
+    /// Hash the whole window and submit the best solution found.
+    Max,
+    /// Hash the whole window, and past the deadline until the target difficulty is reached
+    /// or the liveness limit passes, whichever comes first.
+    TargetThenMax,
+}
+
+impl MineStrategy {
+    pub fn should_stop(
+        &self,
+        best_difficulty: u32,
+        target_difficulty: u32,
+        min_difficulty: u32,
+        past_deadline: bool,
+        past_liveness: bool,
+    ) -> bool {
+        // Never stop without something the program would accept
+        if best_difficulty < min_difficulty {
+            return false;
+        }
+        match self {
+            MineStrategy::Min => best_difficulty >= target_difficulty || past_deadline,
+            MineStrategy::Max => past_deadline,
+            MineStrategy::TargetThenMax => {
+                past_liveness || (past_deadline && best_difficulty >= target_difficulty)
+            }
+        }
     }
 }
 
-fn calculate_multiplier(balance: u64, top_balance: u64) -> f64 {
-    1.0 + (balance as f64 / top_balance as f64).min(1.0f64)
+/// Returns the first nonce of worker `i` out of `threads` on the machine at `offset`
+/// out of `stride` machines. Each worker owns a disjoint range of the nonce space and
+/// walks it sequentially.
+fn first_nonce(offset: u64, stride: u64, threads: u64, i: u64) -> u64 {
+    let machine_range = u64::MAX.saturating_div(stride);
+    let worker_range = machine_range.saturating_div(threads);
+    machine_range
+        .saturating_mul(offset)
+        .saturating_add(worker_range.saturating_mul(i))
 }
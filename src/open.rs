@@ -1,6 +1,12 @@
+use colored::*;
 use solana_sdk::signature::Signer;
 
-use crate::{ send_and_confirm::ComputeBudget, utils::proof_pubkey, Miner, DEFAULT_JITO_TIP };
+use crate::{
+    send_and_confirm::{ ComputeBudget, TxOutcome },
+    utils::proof_pubkey,
+    Miner,
+    DEFAULT_JITO_TIP,
+};
 
 impl Miner {
     pub async fn open(&self) {
@@ -14,6 +20,16 @@ impl Miner {
         // Sign and send transaction.
         println!("Generating challenge...");
         let ix = ore_api::instruction::open(signer.pubkey(), signer.pubkey(), signer.pubkey());
-        self.send_and_confirm(&[ix], ComputeBudget::Dynamic, DEFAULT_JITO_TIP).await.ok();
+        match self.send_and_confirm(&[ix], ComputeBudget::Dynamic, DEFAULT_JITO_TIP).await {
+            Ok(TxOutcome::Landed(_)) => {}
+            Ok(outcome) => {
+                println!("{} Failed to open proof account: {}", "ERROR".bold().red(), outcome);
+                std::process::exit(1);
+            }
+            Err(err) => {
+                println!("{} Failed to open proof account: {}", "ERROR".bold().red(), err);
+                std::process::exit(1);
+            }
+        }
     }
 }
@@ -0,0 +1,69 @@
+use clap::ValueEnum;
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_sdk::signature::Signer;
+
+use crate::Miner;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum PriorityFeeStrategy {
+    /// Pay the fixed --priority-fee on every transaction.
+    Static,
+    /// Follow recent prioritization fees paid for the accounts a transaction writes to.
+    Dynamic,
+}
+
+/// Settings used to price the compute units of each transaction.
+#[derive(Clone, Copy, Debug)]
+pub struct PriorityFeeConfig {
+    pub strategy: PriorityFeeStrategy,
+    pub fee: u64,
+    pub percentile: u8,
+    pub min: u64,
+    pub max: u64,
+}
+
+impl Miner {
+    /// Returns the priority fee, in microlamports per compute unit, to attach to a
+    /// transaction built from the given instructions.
+    pub async fn estimate_priority_fee(&self, ixs: &[Instruction]) -> u64 {
+        let config = self.priority_fee;
+        match config.strategy {
+            PriorityFeeStrategy::Static => config.fee,
+            PriorityFeeStrategy::Dynamic => {
+                // Collect writable accounts, skipping the fee payer
+                let payer = self.signer().pubkey();
+                let mut accounts: Vec<Pubkey> = ixs
+                    .iter()
+                    .flat_map(|ix| ix.accounts.iter())
+                    .filter(|meta| meta.is_writable && meta.pubkey.ne(&payer))
+                    .map(|meta| meta.pubkey)
+                    .collect();
+                accounts.sort();
+                accounts.dedup();
+
+                // Fall back to the static fee if recent fees are unavailable
+                let fee = match self
+                    .rpc_client
+                    .get_recent_prioritization_fees(&accounts)
+                    .await
+                {
+                    Ok(fees) if !fees.is_empty() => {
+                        let mut fees: Vec<u64> =
+                            fees.iter().map(|f| f.prioritization_fee).collect();
+                        fees.sort_unstable();
+                        percentile(&fees, config.percentile)
+                    }
+                    _ => config.fee,
+                };
+                fee.clamp(config.min, config.max.max(config.min))
+            }
+        }
+    }
+}
+
+/// Returns the value at the given percentile of a sorted, non-empty slice.
+fn percentile(sorted: &[u64], percentile: u8) -> u64 {
+    let p = percentile.min(100) as usize;
+    let i = (sorted.len() - 1) * p / 100;
+    sorted[i]
+}
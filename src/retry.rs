@@ -0,0 +1,121 @@
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use solana_client::{
+    client_error::{reqwest::StatusCode, ClientError, ClientErrorKind},
+    rpc_custom_error::{
+        JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE,
+        JSON_RPC_SERVER_ERROR_TRANSACTION_PRECOMPILE_VERIFICATION_FAILURE,
+        JSON_RPC_SERVER_ERROR_TRANSACTION_SIGNATURE_LEN_MISMATCH,
+        JSON_RPC_SERVER_ERROR_TRANSACTION_SIGNATURE_VERIFICATION_FAILURE,
+        JSON_RPC_SERVER_ERROR_UNSUPPORTED_TRANSACTION_VERSION,
+    },
+    rpc_request::RpcError,
+};
+
+/// The upper bound on a single backoff delay.
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// Rate limited requests back off this many times longer than other transient errors.
+const RATE_LIMIT_MULTIPLIER: u32 = 4;
+
+/// JSON-RPC error codes that some providers use to signal rate limiting.
+const RATE_LIMIT_CODES: [i64; 2] = [429, -32429];
+
+/// How a failed RPC request should be treated by the retry loop.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// The request may succeed if sent again.
+    Transient,
+    /// The provider asked us to slow down.
+    RateLimited,
+    /// The request will never succeed, so retrying only wastes time.
+    Permanent,
+}
+
+impl ErrorClass {
+    pub fn of(err: &ClientError) -> Self {
+        match &err.kind {
+            ClientErrorKind::Reqwest(err) => match err.status() {
+                Some(StatusCode::TOO_MANY_REQUESTS) => ErrorClass::RateLimited,
+                Some(StatusCode::UNAUTHORIZED) | Some(StatusCode::FORBIDDEN) => {
+                    ErrorClass::Permanent
+                }
+                _ => ErrorClass::Transient,
+            },
+            ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => match *code {
+                code if RATE_LIMIT_CODES.contains(&code) => ErrorClass::RateLimited,
+                JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE
+                | JSON_RPC_SERVER_ERROR_TRANSACTION_SIGNATURE_VERIFICATION_FAILURE
+                | JSON_RPC_SERVER_ERROR_TRANSACTION_PRECOMPILE_VERIFICATION_FAILURE
+                | JSON_RPC_SERVER_ERROR_TRANSACTION_SIGNATURE_LEN_MISMATCH
+                | JSON_RPC_SERVER_ERROR_UNSUPPORTED_TRANSACTION_VERSION => ErrorClass::Permanent,
+                _ => ErrorClass::Transient,
+            },
+            ClientErrorKind::SigningError(_) | ClientErrorKind::TransactionError(_) => {
+                ErrorClass::Permanent
+            }
+            _ => ErrorClass::Transient,
+        }
+    }
+}
+
+/// Limits on how often and for how long a failing RPC request is retried.
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    pub max_attempts: usize,
+    pub base_delay: Duration,
+    pub deadline: Duration,
+}
+
+impl RetryPolicy {
+    pub fn start(&self) -> Retry {
+        Retry {
+            policy: *self,
+            attempts: 0,
+            started_at: Instant::now(),
+        }
+    }
+}
+
+/// Tracks the attempts made for one request under a `RetryPolicy`.
+pub struct Retry {
+    policy: RetryPolicy,
+    attempts: usize,
+    started_at: Instant,
+}
+
+impl Retry {
+    /// Starts a fresh budget after a successful request, so earlier failures do not
+    /// count against the next one.
+    pub fn reset(&mut self) {
+        self.attempts = 0;
+        self.started_at = Instant::now();
+    }
+
+    /// Records a failed attempt and returns how long to wait before the next one,
+    /// or `None` if the request should be abandoned.
+    pub fn backoff(&mut self, err: &ClientError) -> Option<Duration> {
+        self.attempts += 1;
+        let class = ErrorClass::of(err);
+        if class == ErrorClass::Permanent || self.attempts >= self.policy.max_attempts {
+            return None;
+        }
+
+        // Exponential backoff with jitter in [delay / 2, delay]
+        let exp = 2u32.saturating_pow(self.attempts.saturating_sub(1) as u32);
+        let mut delay = self.policy.base_delay.saturating_mul(exp).min(MAX_BACKOFF);
+        if class == ErrorClass::RateLimited {
+            delay = delay.saturating_mul(RATE_LIMIT_MULTIPLIER);
+        }
+        let half = delay / 2;
+        let jitter = rand::thread_rng().gen_range(0..=half.as_millis() as u64);
+        let delay = half + Duration::from_millis(jitter);
+
+        // Give up if the wait would run past the deadline
+        if self.started_at.elapsed() + delay > self.policy.deadline {
+            return None;
+        }
+        Some(delay)
+    }
+}
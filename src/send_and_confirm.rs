@@ -1,84 +1,121 @@
-use std::str::FromStr;
-use rand::prelude::SliceRandom;
+use std::{ fmt, time::{ Duration, Instant } };
 use colored::*;
-use solana_client::{ client_error::Result as ClientResult, rpc_config::RpcSendTransactionConfig };
+use solana_client::{
+    client_error::{ ClientErrorKind, Result as ClientResult },
+    rpc_config::{ RpcSendTransactionConfig, RpcSimulateTransactionConfig },
+};
 use solana_program::instruction::Instruction;
 
 use solana_rpc_client::spinner;
 use solana_sdk::{
-    commitment_config::CommitmentLevel,
+    commitment_config::{ CommitmentConfig, CommitmentLevel },
     compute_budget::ComputeBudgetInstruction,
-    pubkey::Pubkey,
     signature::{ Signature, Signer },
     system_instruction::transfer,
-    transaction::Transaction,
+    transaction::{ Transaction, TransactionError },
 };
 use solana_transaction_status::UiTransactionEncoding;
 
-use crate::Miner;
+use crate::{ jito::JitoClient, Miner };
 
 const RPC_RETRIES: usize = 0;
-const _SIMULATION_RETRIES: usize = 4;
+const SIMULATION_RETRIES: usize = 4;
+const MAX_COMPUTE_UNITS: u32 = 1_400_000;
+const CONFIRM_DELAY: u64 = 500;
+const RESUBMIT_DELAY: u64 = 2000;
+const BLOCKHASH_RETRIES: usize = 3;
+const BLOCKHASH_TIMEOUT: u64 = 90_000;
 
 pub enum ComputeBudget {
     Dynamic,
     Fixed(u32),
 }
 
+/// The final state of a transaction submitted through `send_and_confirm`.
+#[derive(Debug)]
+pub enum TxOutcome {
+    /// The transaction reached the client's commitment level without error.
+    Landed(Signature),
+    /// The transaction was included in a block but failed to execute.
+    Failed(Signature, TransactionError),
+    /// The transaction never landed before its last blockhash expired.
+    Expired(Signature),
+}
+
+/// The status of a submission while it is being confirmed.
+pub enum Confirmation {
+    /// The transaction or bundle has not been seen on chain.
+    Unknown,
+    /// The transaction or bundle was processed but has not reached the desired commitment.
+    Pending,
+    Landed,
+    Failed(TransactionError),
+}
+
+impl Confirmation {
+    /// Orders confirmations by how far the submission has progressed.
+    pub fn rank(&self) -> u8 {
+        match self {
+            Confirmation::Unknown => 0,
+            Confirmation::Pending => 1,
+            Confirmation::Landed | Confirmation::Failed(_) => 2,
+        }
+    }
+}
+
+impl TxOutcome {
+    pub fn signature(&self) -> Signature {
+        match self {
+            TxOutcome::Landed(sig) | TxOutcome::Failed(sig, _) | TxOutcome::Expired(sig) => *sig,
+        }
+    }
+}
+
+impl fmt::Display for TxOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TxOutcome::Landed(sig) => write!(f, "{} {}", "OK".bold().green(), sig),
+            TxOutcome::Failed(sig, err) => write!(f, "{} {} ({})", "FAILED".bold().red(), sig, err),
+            TxOutcome::Expired(sig) => write!(f, "{} {}", "EXPIRED".bold().yellow(), sig),
+        }
+    }
+}
+
 impl Miner {
     pub async fn send_and_confirm(
         &self,
         ixs: &[Instruction],
         compute_budget: ComputeBudget,
         tip: u64
-    ) -> ClientResult<Signature> {
+    ) -> ClientResult<TxOutcome> {
         let progress_bar = spinner::new_progress_bar();
         let signer = self.signer();
-        let client = self.rpc_client.clone();
-        let mut send_client = self.rpc_client.clone();
-
-        if tip > 0 {
-            send_client = self.send_client.clone();
-        }
+        let client = self.write_client.clone();
 
         // Set compute units
         let mut final_ixs = vec![];
 
         match compute_budget {
             ComputeBudget::Dynamic => {
-                final_ixs.push(ComputeBudgetInstruction::set_compute_unit_limit(1_400_000));
+                // Replaced with the simulated limit once the tx is assembled
+                final_ixs.push(ComputeBudgetInstruction::set_compute_unit_limit(MAX_COMPUTE_UNITS));
             }
             ComputeBudget::Fixed(cus) => {
                 final_ixs.push(ComputeBudgetInstruction::set_compute_unit_limit(cus));
             }
         }
 
-        if tip > 0 {
-            let tips = [
-                "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
-                "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
-                "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
-                "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
-                "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
-                "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
-                "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
-                "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
-            ];
-
-            final_ixs.push(
-                transfer(
-                    &signer.pubkey(),
-                    &Pubkey::from_str(
-                        &tips.choose(&mut rand::thread_rng()).unwrap().to_string()
-                    ).unwrap(),
-                    tip
-                )
-            );
-        }
-
-        final_ixs.push(ComputeBudgetInstruction::set_compute_unit_price(self.priority_fee));
+        let priority_fee = self.estimate_priority_fee(ixs).await;
+        final_ixs.push(ComputeBudgetInstruction::set_compute_unit_price(priority_fee));
         final_ixs.extend_from_slice(ixs);
 
+        // Estimate compute units
+        if let ComputeBudget::Dynamic = compute_budget {
+            progress_bar.set_message("Simulating transaction...");
+            let cus = self.simulate_compute_units(&final_ixs).await?;
+            final_ixs[0] = ComputeBudgetInstruction::set_compute_unit_limit(cus);
+        }
+
         // Build tx
         let send_cfg = RpcSendTransactionConfig {
             skip_preflight: true,
@@ -88,27 +125,222 @@ impl Miner {
             min_context_slot: None,
         };
         let mut tx = Transaction::new_with_payer(&final_ixs, Some(&signer.pubkey()));
+        let mut tip_tx = match &self.jito {
+            Some(jito) if tip > 0 => {
+                let tip_ix = transfer(&signer.pubkey(), &jito.tip_account(), tip);
+                Some(Transaction::new_with_payer(&[tip_ix], Some(&signer.pubkey())))
+            }
+            _ => None,
+        };
 
-        // Sign tx
-        let (hash, _slot) = client
-            .get_latest_blockhash_with_commitment(self.rpc_client.commitment()).await
-            .unwrap();
-        tx.sign(&[&signer], hash);
-
+        // Each kind of request gets its own retry budget, reset after every success
+        let mut blockhash_retry = self.retry_policy.start();
+        let mut send_retry = self.retry_policy.start();
+        let mut poll_retry = self.retry_policy.start();
+        let mut blockhash_attempts = 0;
         loop {
-            progress_bar.set_message(format!("Submitting transaction..."));
-            match send_client.send_transaction_with_config(&tx, send_cfg).await {
-                Ok(sig) => {
-                    progress_bar.finish_with_message(format!("Sent: {}", sig));
-                    return Ok(sig);
+            // Sign tx with a fresh blockhash
+            let (hash, last_valid_block_height) = match
+                client.get_latest_blockhash_with_commitment(client.commitment()).await
+            {
+                Ok(blockhash) => {
+                    blockhash_retry.reset();
+                    blockhash
                 }
-
-                // Handle submit errors
                 Err(err) => {
+                    let Some(delay) = blockhash_retry.backoff(&err) else {
+                        progress_bar.finish_with_message(
+                            format!("{}: {}", "ERROR".bold().red(), err.kind())
+                        );
+                        return Err(err);
+                    };
                     progress_bar.set_message(
-                        format!("{}: {}", "ERROR".bold().red(), err.kind().to_string())
+                        format!("{}: {} (retrying in {:?})", "ERROR".bold().red(), err.kind(), delay)
                     );
+                    tokio::time::sleep(delay).await;
+                    continue;
                 }
+            };
+            tx.sign(&[&signer], hash);
+            if let Some(tip_tx) = tip_tx.as_mut() {
+                tip_tx.sign(&[&signer], hash);
+            }
+            let sig = tx.signatures[0];
+            let signed_at = Instant::now();
+            blockhash_attempts += 1;
+
+            // Resubmit on a fixed cadence until the tx lands or the blockhash expires
+            let mut last_submit: Option<Instant> = None;
+            let mut bundle_id: Option<String> = None;
+            loop {
+                if last_submit.map_or(true, |t| t.elapsed() >= Duration::from_millis(RESUBMIT_DELAY)) {
+                    progress_bar.set_message("Submitting transaction...");
+                    // Tips are paid in a separate transaction bundled with this one
+                    let submission = match (&self.jito, tip_tx.as_ref()) {
+                        (Some(jito), Some(tip_tx)) => {
+                            jito.send_bundle(&[&tx, tip_tx]).await.map(Some)
+                        }
+                        _ => self.send_pool.broadcast(&tx, send_cfg).await.map(|_| None),
+                    };
+                    match submission {
+                        Ok(id) => {
+                            send_retry.reset();
+                            last_submit = Some(Instant::now());
+                            if id.is_some() {
+                                bundle_id = id;
+                            }
+                        }
+
+                        // Handle submit errors
+                        Err(err) => {
+                            let Some(delay) = send_retry.backoff(&err) else {
+                                // An earlier submission may still land, so keep confirming it
+                                if last_submit.is_some() {
+                                    send_retry.reset();
+                                    last_submit = Some(Instant::now());
+                                    continue;
+                                }
+                                progress_bar.finish_with_message(
+                                    format!("{}: {}", "ERROR".bold().red(), err.kind())
+                                );
+                                return Err(err);
+                            };
+                            progress_bar.set_message(
+                                format!(
+                                    "{}: {} (retrying in {:?})",
+                                    "ERROR".bold().red(),
+                                    err.kind(),
+                                    delay
+                                )
+                            );
+                            tokio::time::sleep(delay).await;
+                            continue;
+                        }
+                    }
+                }
+
+                // Confirm tx
+                tokio::time::sleep(Duration::from_millis(CONFIRM_DELAY)).await;
+                progress_bar.set_message(format!("Confirming transaction {}...", sig));
+                let confirmation = match (&self.jito, bundle_id.as_deref()) {
+                    (Some(jito), Some(id)) => Self::confirm_bundle(jito, id, client.commitment()).await,
+                    _ => self.send_pool.confirm(&sig, client.commitment()).await,
+                };
+                if confirmation.is_ok() {
+                    poll_retry.reset();
+                }
+                match confirmation {
+                    Ok(Confirmation::Landed) => {
+                        let outcome = TxOutcome::Landed(sig);
+                        progress_bar.finish_with_message(outcome.to_string());
+                        return Ok(outcome);
+                    }
+                    Ok(Confirmation::Failed(err)) => {
+                        let outcome = TxOutcome::Failed(sig, err);
+                        progress_bar.finish_with_message(outcome.to_string());
+                        return Ok(outcome);
+                    }
+
+                    // Processed but not yet at the desired commitment
+                    Ok(Confirmation::Pending) => {
+                        continue;
+                    }
+                    Ok(Confirmation::Unknown) => {}
+
+                    // The tx may already be in flight, so keep polling until its blockhash expires
+                    Err(err) => {
+                        let delay = poll_retry.backoff(&err).unwrap_or_else(|| {
+                            poll_retry.reset();
+                            Duration::ZERO
+                        });
+                        progress_bar.set_message(
+                            format!("{}: {} (retrying in {:?})", "ERROR".bold().red(), err.kind(), delay)
+                        );
+                        tokio::time::sleep(delay).await;
+                    }
+                }
+
+                // Check for blockhash expiry, falling back to elapsed time if the block
+                // height is unavailable
+                match client.get_block_height().await {
+                    Ok(block_height) if block_height > last_valid_block_height => {
+                        break;
+                    }
+                    Ok(_) => {}
+                    Err(_) if signed_at.elapsed() >= Duration::from_millis(BLOCKHASH_TIMEOUT) => {
+                        break;
+                    }
+                    Err(_) => {}
+                }
+            }
+
+            if blockhash_attempts >= BLOCKHASH_RETRIES {
+                let outcome = TxOutcome::Expired(tx.signatures[0]);
+                progress_bar.finish_with_message(outcome.to_string());
+                return Ok(outcome);
+            }
+            progress_bar.set_message("Blockhash expired. Re-signing transaction...");
+        }
+    }
+
+    async fn confirm_bundle(
+        jito: &JitoClient,
+        bundle_id: &str,
+        commitment: CommitmentConfig
+    ) -> ClientResult<Confirmation> {
+        let status = jito.get_bundle_status(bundle_id).await?;
+        Ok(match status {
+            None => Confirmation::Unknown,
+            Some(status) =>
+                match status.err {
+                    Err(err) => Confirmation::Failed(err),
+                    Ok(()) if status.satisfies_commitment(commitment) => Confirmation::Landed,
+                    Ok(()) => Confirmation::Pending,
+                }
+        })
+    }
+
+    /// Simulates the transaction and returns the compute units it consumed plus the
+    /// configured safety margin, capped at the maximum a transaction may request.
+    async fn simulate_compute_units(&self, ixs: &[Instruction]) -> ClientResult<u32> {
+        let signer = self.signer();
+        let tx = Transaction::new_with_payer(ixs, Some(&signer.pubkey()));
+        let sim_cfg = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(self.write_client.commitment()),
+            encoding: Some(UiTransactionEncoding::Base64),
+            ..RpcSimulateTransactionConfig::default()
+        };
+
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match
+                self.write_client.simulate_transaction_with_config(&tx, sim_cfg.clone()).await
+            {
+                Ok(sim) => {
+                    if let Some(err) = sim.value.err {
+                        return Err(ClientErrorKind::TransactionError(err).into());
+                    }
+                    if let Some(units_consumed) = sim.value.units_consumed {
+                        let margin = units_consumed
+                            .saturating_mul(self.compute_unit_margin)
+                            .saturating_div(100);
+                        let cus = units_consumed.saturating_add(margin);
+                        return Ok(cus.min(MAX_COMPUTE_UNITS as u64) as u32);
+                    }
+                }
+                Err(err) => {
+                    if attempts >= SIMULATION_RETRIES {
+                        return Err(err);
+                    }
+                }
+            }
+
+            // Fall back to the maximum if the node never reports consumed units
+            if attempts >= SIMULATION_RETRIES {
+                return Ok(MAX_COMPUTE_UNITS);
             }
         }
     }
@@ -0,0 +1,245 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use colored::*;
+use futures::{stream::FuturesUnordered, Future, StreamExt};
+use solana_client::{
+    client_error::Result as ClientResult, nonblocking::rpc_client::RpcClient,
+    rpc_config::RpcSendTransactionConfig,
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig, signature::Signature, transaction::Transaction,
+};
+
+use crate::send_and_confirm::Confirmation;
+
+/// Consecutive failures after which an endpoint is demoted.
+const DEMOTION_THRESHOLD: u32 = 3;
+
+/// How long a demoted endpoint is left out of broadcasts.
+const DEMOTION_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// How long a status poll waits for slower endpoints once one has answered.
+const STRAGGLER_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Weight given to the newest sample in the latency moving average.
+const LATENCY_SMOOTHING: f64 = 0.2;
+
+#[derive(Default)]
+struct EndpointStats {
+    successes: u64,
+    failures: u64,
+    consecutive_failures: u32,
+    latency_ms: Option<f64>,
+    demoted_until: Option<Instant>,
+}
+
+struct Endpoint {
+    client: Arc<RpcClient>,
+    stats: Mutex<EndpointStats>,
+}
+
+impl Endpoint {
+    fn is_demoted(&self) -> bool {
+        let stats = self.stats.lock().unwrap();
+        stats.demoted_until.map_or(false, |t| Instant::now() < t)
+    }
+
+    fn record_success(&self, latency: Duration) {
+        let mut stats = self.stats.lock().unwrap();
+        let sample = latency.as_secs_f64() * 1000.0;
+        stats.successes += 1;
+        stats.consecutive_failures = 0;
+        stats.demoted_until = None;
+        stats.latency_ms = Some(match stats.latency_ms {
+            Some(avg) => avg + LATENCY_SMOOTHING * (sample - avg),
+            None => sample,
+        });
+    }
+
+    fn record_failure(&self) {
+        let mut stats = self.stats.lock().unwrap();
+        stats.failures += 1;
+        stats.consecutive_failures += 1;
+        if stats.consecutive_failures < DEMOTION_THRESHOLD {
+            return;
+        }
+        stats.demoted_until = Some(Instant::now() + DEMOTION_COOLDOWN);
+        if stats.consecutive_failures == DEMOTION_THRESHOLD {
+            println!(
+                "{} Demoting {} for {}s after {} consecutive failures ({}/{} sends succeeded, avg latency {})",
+                "WARNING".bold().yellow(),
+                self.client.url(),
+                DEMOTION_COOLDOWN.as_secs(),
+                stats.consecutive_failures,
+                stats.successes,
+                stats.successes + stats.failures,
+                stats
+                    .latency_ms
+                    .map_or("n/a".to_string(), |ms| format!("{:.0}ms", ms)),
+            );
+        }
+    }
+}
+
+/// A set of RPC endpoints that every transaction is broadcast to at once.
+pub struct SendPool {
+    endpoints: Vec<Arc<Endpoint>>,
+}
+
+impl SendPool {
+    pub fn new(clients: Vec<Arc<RpcClient>>) -> Self {
+        let endpoints = clients
+            .into_iter()
+            .map(|client| {
+                Arc::new(Endpoint {
+                    client,
+                    stats: Mutex::new(EndpointStats::default()),
+                })
+            })
+            .collect();
+        Self { endpoints }
+    }
+
+    /// Returns the endpoints that are not demoted, or all of them if every one is.
+    fn active(&self) -> Vec<Arc<Endpoint>> {
+        let active: Vec<Arc<Endpoint>> = self
+            .endpoints
+            .iter()
+            .filter(|e| !e.is_demoted())
+            .cloned()
+            .collect();
+        if active.is_empty() {
+            self.endpoints.clone()
+        } else {
+            active
+        }
+    }
+
+    /// Sends the transaction to every active endpoint. Succeeds as soon as any endpoint
+    /// accepts it, otherwise returns the first error. Slower endpoints finish in the
+    /// background so their stats are still recorded.
+    pub async fn broadcast(
+        &self,
+        tx: &Transaction,
+        config: RpcSendTransactionConfig,
+    ) -> ClientResult<Signature> {
+        let tx = Arc::new(tx.clone());
+        let mut pending: FuturesUnordered<_> = self
+            .active()
+            .into_iter()
+            .map(|endpoint| {
+                let tx = Arc::clone(&tx);
+                async move {
+                    let timer = Instant::now();
+                    let result = endpoint
+                        .client
+                        .send_transaction_with_config(&*tx, config)
+                        .await;
+                    match &result {
+                        Ok(_) => endpoint.record_success(timer.elapsed()),
+                        Err(_) => endpoint.record_failure(),
+                    }
+                    result
+                }
+            })
+            .collect();
+
+        let mut first_err = None;
+        while let Some(result) = pending.next().await {
+            match result {
+                Ok(sig) => {
+                    finish_in_background(pending);
+                    return Ok(sig);
+                }
+                Err(err) => {
+                    first_err.get_or_insert(err);
+                }
+            }
+        }
+        Err(first_err.expect("Send pool has at least one endpoint"))
+    }
+
+    /// Asks every active endpoint for the status of the transaction. Returns as soon as
+    /// one endpoint reports it landed or failed. Otherwise returns the most advanced
+    /// answer, waiting at most `STRAGGLER_TIMEOUT` for slow endpoints once one has
+    /// answered.
+    pub async fn confirm(
+        &self,
+        sig: &Signature,
+        commitment: CommitmentConfig,
+    ) -> ClientResult<Confirmation> {
+        let sig = *sig;
+        let mut pending: FuturesUnordered<_> = self
+            .active()
+            .into_iter()
+            .map(|endpoint| async move {
+                let timer = Instant::now();
+                let result = endpoint.client.get_signature_statuses(&[sig]).await;
+                match &result {
+                    Ok(_) => endpoint.record_success(timer.elapsed()),
+                    Err(_) => endpoint.record_failure(),
+                }
+                Ok(match result?.value.into_iter().next().flatten() {
+                    None => Confirmation::Unknown,
+                    Some(status) => match status.err {
+                        Some(err) => Confirmation::Failed(err),
+                        None if status.satisfies_commitment(commitment) => Confirmation::Landed,
+                        None => Confirmation::Pending,
+                    },
+                })
+            })
+            .collect();
+
+        let mut best: Option<Confirmation> = None;
+        let mut first_err = None;
+        loop {
+            let next = match best {
+                Some(_) => match tokio::time::timeout(STRAGGLER_TIMEOUT, pending.next()).await {
+                    Ok(next) => next,
+                    Err(_) => break,
+                },
+                None => pending.next().await,
+            };
+            let Some(result) = next else {
+                break;
+            };
+            match result {
+                Ok(confirmation @ (Confirmation::Landed | Confirmation::Failed(_))) => {
+                    best = Some(confirmation);
+                    break;
+                }
+                Ok(confirmation) => {
+                    if best
+                        .as_ref()
+                        .map_or(true, |b| confirmation.rank() > b.rank())
+                    {
+                        best = Some(confirmation);
+                    }
+                }
+                Err(err) => {
+                    first_err.get_or_insert(err);
+                }
+            }
+        }
+        finish_in_background(pending);
+        match best {
+            Some(confirmation) => Ok(confirmation),
+            None => Err(first_err.expect("Send pool has at least one endpoint")),
+        }
+    }
+}
+
+/// Drives the remaining requests to completion on a spawned task.
+fn finish_in_background<F>(mut pending: FuturesUnordered<F>)
+where
+    F: Future + Send + 'static,
+    F::Output: Send,
+{
+    if pending.is_empty() {
+        return;
+    }
+    tokio::spawn(async move { while pending.next().await.is_some() {} });
+}
@@ -0,0 +1,129 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use colored::*;
+use solana_sdk::{native_token::lamports_to_sol, signature::Signature};
+
+use crate::utils::{amount_u64_to_f64, amount_u64_to_string};
+
+/// What happened in one mining round.
+#[derive(Debug, Default)]
+pub struct Round {
+    pub difficulty: u32,
+    pub hashes: u64,
+    pub signature: Option<Signature>,
+    pub landed: bool,
+    /// The reward earned, known once the next proof has been fetched.
+    pub reward: Option<u64>,
+    /// The reward expected when the solution landed, before the bus capped it.
+    pub expected_reward: u64,
+    /// Transaction fees paid, in lamports, tips excluded.
+    pub fees: u64,
+    /// Jito tip paid, in lamports.
+    pub tip: u64,
+}
+
+/// Statistics for every round mined since the miner started.
+pub struct MiningSession {
+    started_at: Instant,
+    rounds: Vec<Round>,
+}
+
+impl MiningSession {
+    pub fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            rounds: vec![],
+        }
+    }
+
+    pub fn record(&mut self, round: Round) {
+        self.rounds.push(round);
+    }
+
+    /// Returns true if the latest round submitted a solution.
+    pub fn last_round_submitted(&self) -> bool {
+        self.rounds
+            .last()
+            .map_or(false, |round| round.signature.is_some())
+    }
+
+    /// Returns true if confirmation reported the latest round's submission landed.
+    pub fn last_round_landed(&self) -> bool {
+        self.rounds.last().map_or(false, |round| round.landed)
+    }
+
+    /// Returns the reward the latest round expected when its solution landed.
+    pub fn last_round_expected_reward(&self) -> u64 {
+        self.rounds.last().map_or(0, |round| round.expected_reward)
+    }
+
+    /// Settles the latest round once a proof at least as fresh as its confirmation shows
+    /// whether it landed.
+    pub fn settle_last_round(&mut self, reward: Option<u64>) {
+        if let Some(round) = self.rounds.last_mut() {
+            if round.signature.is_some() {
+                round.landed = reward.is_some();
+                round.reward = reward;
+            }
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        let elapsed = self.started_at.elapsed();
+        let submitted = self.rounds.iter().filter(|r| r.signature.is_some()).count();
+        let landed: Vec<&Round> = self.rounds.iter().filter(|r| r.landed).collect();
+        let hashes: u64 = self.rounds.iter().map(|r| r.hashes).sum();
+        let rewards: u64 = landed.iter().filter_map(|r| r.reward).sum();
+        let fees: u64 = self.rounds.iter().map(|r| r.fees).sum();
+        let tips: u64 = self.rounds.iter().map(|r| r.tip).sum();
+        let landing_rate = match submitted {
+            0 => 0.0,
+            n => landed.len() as f64 / n as f64 * 100.0,
+        };
+        let mean_difficulty = match self.rounds.len() {
+            0 => 0.0,
+            n => self.rounds.iter().map(|r| r.difficulty as f64).sum::<f64>() / n as f64,
+        };
+        let hours = elapsed.as_secs_f64() / 3600.0;
+        format!(
+            "{} {} rounds in {}m {}s\n  Landed: {}/{} ({:.1}%)\n  Mean difficulty: {:.1}\n  Hashes: {}\n  Rewards: {} ORE ({:.6} ORE/h)\n  Fees: {} SOL  Tips: {} SOL",
+            "Session".bold(),
+            self.rounds.len(),
+            elapsed.as_secs() / 60,
+            elapsed.as_secs() % 60,
+            landed.len(),
+            submitted,
+            landing_rate,
+            mean_difficulty,
+            hashes,
+            amount_u64_to_string(rewards),
+            if hours > 0.0 {
+                amount_u64_to_f64(rewards) / hours
+            } else {
+                0.0
+            },
+            lamports_to_sol(fees),
+            lamports_to_sol(tips),
+        )
+    }
+
+    /// Prints the summary whenever the process receives SIGUSR1.
+    #[cfg(unix)]
+    pub fn print_on_signal(session: Arc<Mutex<Self>>) {
+        use tokio::signal::unix::{signal, SignalKind};
+        let Ok(mut sigusr1) = signal(SignalKind::user_defined1()) else {
+            return;
+        };
+        tokio::spawn(async move {
+            while sigusr1.recv().await.is_some() {
+                println!("\n{}", session.lock().unwrap().summary());
+            }
+        });
+    }
+
+    #[cfg(not(unix))]
+    pub fn print_on_signal(_session: Arc<Mutex<Self>>) {}
+}
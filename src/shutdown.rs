@@ -0,0 +1,61 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use colored::*;
+
+/// Set once the process has been asked to stop with Ctrl-C or SIGTERM. A second
+/// signal exits immediately.
+#[derive(Clone)]
+pub struct Shutdown {
+    requested: Arc<AtomicBool>,
+}
+
+impl Shutdown {
+    pub fn listen() -> Self {
+        let shutdown = Shutdown {
+            requested: Arc::new(AtomicBool::new(false)),
+        };
+        let requested = Arc::clone(&shutdown.requested);
+        tokio::spawn(async move {
+            loop {
+                wait_for_signal().await;
+                if requested.swap(true, Ordering::Relaxed) {
+                    std::process::exit(130);
+                }
+                println!(
+                    "\n{} Shutting down after the current round. Signal again to exit now.",
+                    "INFO".bold().blue()
+                );
+            }
+        });
+        shutdown
+    }
+
+    pub fn is_requested(&self) -> bool {
+        self.requested.load(Ordering::Relaxed)
+    }
+
+    /// Returns the flag so that blocking threads can poll it.
+    pub fn flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.requested)
+    }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+    let mut sigterm = signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM");
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = sigterm.recv() => {}
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() {
+    tokio::signal::ctrl_c()
+        .await
+        .expect("Failed to listen for Ctrl-C");
+}
@@ -1,12 +1,13 @@
 use std::str::FromStr;
 
+use colored::*;
 use solana_program::pubkey::Pubkey;
 use solana_sdk::signature::Signer;
 
 use crate::{
     args::StakeArgs,
     cu_limits::CU_LIMIT_CLAIM,
-    send_and_confirm::ComputeBudget,
+    send_and_confirm::{ComputeBudget, TxOutcome},
     utils::amount_f64_to_u64,
     Miner, DEFAULT_JITO_TIP,
 };
@@ -41,10 +42,20 @@ impl Miner {
 
         // Send tx
         let ix = ore_api::instruction::stake(signer.pubkey(), sender, amount);
-        self.send_and_confirm(
-            &[ix],
-            ComputeBudget::Fixed(CU_LIMIT_CLAIM),
-            DEFAULT_JITO_TIP
-        ).await.ok();
+        match
+            self.send_and_confirm(
+                &[ix],
+                ComputeBudget::Fixed(CU_LIMIT_CLAIM),
+                DEFAULT_JITO_TIP
+            ).await
+        {
+            Ok(TxOutcome::Landed(_)) => {}
+            Ok(outcome) => {
+                println!("{} Stake did not land: {}", "ERROR".bold().red(), outcome);
+            }
+            Err(err) => {
+                println!("{} {}", "ERROR".bold().red(), err);
+            }
+        }
     }
 }
@@ -0,0 +1,94 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use futures::StreamExt;
+use tokio::sync::RwLock;
+use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+
+use crate::utils::{Tip, TipPercentile};
+
+/// The default Jito websocket that publishes recently landed tips.
+pub const DEFAULT_TIP_STREAM_URL: &str = "ws://bundles-api-rest.jito.wtf/api/v1/bundles/tip_stream";
+
+/// Bounds on the delay between reconnection attempts.
+const MIN_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// Settings for deriving a tip from the Jito tip stream.
+#[derive(Clone, Debug)]
+pub struct TipStreamConfig {
+    pub url: String,
+    pub percentile: TipPercentile,
+    pub multiplier: f64,
+    pub min: u64,
+    pub max: u64,
+    pub stale_after: Duration,
+    pub fallback: u64,
+}
+
+/// A supervised subscription to the Jito tip stream. The subscription reconnects with
+/// backoff whenever the websocket is unreachable or closes, and the latest tip is only
+/// trusted until it goes stale.
+pub struct TipStream {
+    latest: Arc<RwLock<Option<(u64, Instant)>>>,
+    stale_after: Duration,
+    fallback: u64,
+}
+
+impl TipStream {
+    pub fn spawn(config: TipStreamConfig) -> Self {
+        let latest = Arc::new(RwLock::new(None));
+        let stream = TipStream {
+            latest: Arc::clone(&latest),
+            stale_after: config.stale_after,
+            fallback: config
+                .fallback
+                .clamp(config.min, config.max.max(config.min)),
+        };
+        tokio::spawn(supervise(config, latest));
+        stream
+    }
+
+    /// Returns the latest tip in lamports, or the fallback if no fresh tip is available.
+    pub async fn current(&self) -> u64 {
+        match *self.latest.read().await {
+            Some((tip, updated_at)) if updated_at.elapsed() < self.stale_after => tip,
+            _ => self.fallback,
+        }
+    }
+}
+
+async fn supervise(config: TipStreamConfig, latest: Arc<RwLock<Option<(u64, Instant)>>>) {
+    let max = config.max.max(config.min);
+    let mut delay = MIN_RECONNECT_DELAY;
+    loop {
+        if let Ok((ws_stream, _)) = connect_async(config.url.as_str()).await {
+            let (_, mut read) = ws_stream.split();
+
+            // Reconnect if the stream goes quiet for as long as a tip stays fresh
+            while let Ok(Some(message)) =
+                tokio::time::timeout(config.stale_after, read.next()).await
+            {
+                let Ok(Message::Text(text)) = message else {
+                    continue;
+                };
+                if let Ok(tips) = serde_json::from_str::<Vec<Tip>>(&text) {
+                    if let Some(item) = tips.last() {
+                        let lamports = item.landed_tip(config.percentile)
+                            * config.multiplier
+                            * (10_f64).powf(9.0);
+                        let tip = (lamports as u64).clamp(config.min, max);
+                        *latest.write().await = Some((tip, Instant::now()));
+                        delay = MIN_RECONNECT_DELAY;
+                    }
+                }
+            }
+        }
+
+        // Reconnect with exponential backoff
+        tokio::time::sleep(delay).await;
+        delay = delay.saturating_mul(2).min(MAX_RECONNECT_DELAY);
+    }
+}
@@ -1,11 +1,12 @@
 use std::str::FromStr;
 
+use colored::*;
 use ore_api::consts::MINT_ADDRESS;
 use solana_sdk::{ pubkey::Pubkey, signer::Signer };
 
 use crate::{
     cu_limits::CU_LIMIT_UPGRADE,
-    send_and_confirm::ComputeBudget,
+    send_and_confirm::{ ComputeBudget, TxOutcome },
     utils::amount_f64_to_u64_v1,
     Miner,
     UpgradeArgs,
@@ -15,7 +16,9 @@ use crate::{
 impl Miner {
     pub async fn upgrade(&self, args: UpgradeArgs) {
         let signer = &self.signer();
-        let beneficiary = self.get_or_initialize_ata().await;
+        let Some(beneficiary) = self.get_or_initialize_ata().await else {
+            return;
+        };
         let (sender, sender_balance) = self.get_ata_v1().await;
 
         println!("{}", MINT_ADDRESS);
@@ -37,9 +40,12 @@ impl Miner {
                 DEFAULT_JITO_TIP
             ).await
         {
-            Ok(_sig) => {}
+            Ok(TxOutcome::Landed(_)) => {}
+            Ok(outcome) => {
+                println!("{} Upgrade did not land: {}", "ERROR".bold().red(), outcome);
+            }
             Err(err) => {
-                println!("error: {}", err);
+                println!("{} {}", "ERROR".bold().red(), err);
             }
         }
     }
@@ -76,7 +82,7 @@ impl Miner {
         (token_account_pubkey_v1, balance)
     }
 
-    async fn get_or_initialize_ata(&self) -> Pubkey {
+    async fn get_or_initialize_ata(&self) -> Option<Pubkey> {
         // Initialize client
         let signer = self.signer();
         let client = self.rpc_client.clone();
@@ -96,10 +102,24 @@ impl Miner {
                 &Pubkey::from_str("oreoU2P8bN6jkk3jbaiVxYnG1dCXcYxwhwyK9jSybcp").unwrap(),
                 &spl_token::id()
             );
-            self.send_and_confirm(&[ix], ComputeBudget::Dynamic, DEFAULT_JITO_TIP).await.ok();
+            match self.send_and_confirm(&[ix], ComputeBudget::Dynamic, DEFAULT_JITO_TIP).await {
+                Ok(TxOutcome::Landed(_)) => {}
+                Ok(outcome) => {
+                    println!(
+                        "{} Failed to create token account: {}",
+                        "ERROR".bold().red(),
+                        outcome
+                    );
+                    return None;
+                }
+                Err(err) => {
+                    println!("{} {}", "ERROR".bold().red(), err);
+                    return None;
+                }
+            }
         }
 
         // Return token account address
-        token_account_pubkey
+        Some(token_account_pubkey)
     }
 }
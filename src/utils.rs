@@ -1,6 +1,7 @@
 use std::io::Read;
 
 use cached::proc_macro::cached;
+use clap::ValueEnum;
 use ore_api::{
     consts::{
         CONFIG_ADDRESS, MINT_ADDRESS, PROOF, TOKEN_DECIMALS, TOKEN_DECIMALS_V1, TREASURY_ADDRESS,
@@ -11,9 +12,42 @@ use ore_utils::AccountDeserialize;
 use serde::{Deserialize, Serialize};
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_program::{pubkey::Pubkey, sysvar};
-use solana_sdk::clock::Clock;
+use solana_sdk::{clock::Clock, commitment_config::CommitmentConfig};
 use spl_associated_token_account::get_associated_token_address;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Commitment {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl From<Commitment> for CommitmentConfig {
+    fn from(commitment: Commitment) -> Self {
+        match commitment {
+            Commitment::Processed => CommitmentConfig::processed(),
+            Commitment::Confirmed => CommitmentConfig::confirmed(),
+            Commitment::Finalized => CommitmentConfig::finalized(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum TipPercentile {
+    #[value(name = "25")]
+    P25,
+    #[value(name = "50")]
+    P50,
+    #[value(name = "75")]
+    P75,
+    #[value(name = "95")]
+    P95,
+    #[value(name = "99")]
+    P99,
+    #[value(name = "ema")]
+    Ema50,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Tip {
     pub time: String,
@@ -25,6 +59,20 @@ pub struct Tip {
     pub ema_landed_tips_50th_percentile: f64,
 }
 
+impl Tip {
+    /// Returns the landed tip at the given percentile, in SOL.
+    pub fn landed_tip(&self, percentile: TipPercentile) -> f64 {
+        match percentile {
+            TipPercentile::P25 => self.landed_tips_25th_percentile,
+            TipPercentile::P50 => self.landed_tips_50th_percentile,
+            TipPercentile::P75 => self.landed_tips_75th_percentile,
+            TipPercentile::P95 => self.landed_tips_95th_percentile,
+            TipPercentile::P99 => self.landed_tips_99th_percentile,
+            TipPercentile::Ema50 => self.ema_landed_tips_50th_percentile,
+        }
+    }
+}
+
 pub async fn _get_treasury(client: &RpcClient) -> Treasury {
     let data = client
         .get_account_data(&TREASURY_ADDRESS)